@@ -1,6 +1,31 @@
 use super::PartitionKey;
 use lsm_tree::{UserKey, UserValue, ValueType};
 
+/// Checks that a key-value pair fits into the journal format.
+///
+/// Keys and values are serialized with a 16-bit length prefix, so
+/// anything larger would be silently truncated.
+///
+/// Oversized keys or values are reported as an I/O error
+/// of kind [`std::io::ErrorKind::InvalidInput`].
+pub fn check_key_value_size(key: &[u8], value: &[u8]) -> crate::Result<()> {
+    // TODO: dedicated error variants in 2.0.0
+    if u16::try_from(key.len()).is_err() {
+        return Err(invalid_input("key is larger than 65535 bytes"));
+    }
+
+    // TODO: u32 in 2.0.0
+    if u16::try_from(value.len()).is_err() {
+        return Err(invalid_input("value is larger than 65535 bytes"));
+    }
+
+    Ok(())
+}
+
+fn invalid_input(msg: &str) -> crate::Error {
+    crate::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))
+}
+
 pub struct Item {
     /// Partition key - an arbitrary byte array
     ///
@@ -38,6 +63,11 @@ impl std::fmt::Debug for Item {
 }
 
 impl Item {
+    /// Checks that the item fits into the journal format.
+    pub fn check_size(&self) -> crate::Result<()> {
+        check_key_value_size(&self.key, &self.value)
+    }
+
     pub fn new<P: Into<PartitionKey>, K: Into<UserKey>, V: Into<UserValue>>(
         partition: P,
         key: K,
@@ -51,10 +81,6 @@ impl Item {
         assert!(!p.is_empty());
         assert!(!k.is_empty());
         assert!(p.len() <= u8::MAX.into());
-        assert!(k.len() <= u16::MAX.into());
-
-        // TODO: u32 in 2.0.0
-        assert!(u16::try_from(v.len()).is_ok());
 
         Self {
             partition: p,
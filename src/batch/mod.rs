@@ -1,8 +1,8 @@
 pub mod item;
 
-use crate::{Keyspace, PartitionHandle};
+use crate::{partition::watch::WatchEvent, Keyspace, PartitionHandle};
 use item::Item;
-use lsm_tree::{Value, ValueType};
+use lsm_tree::{SeqNo, Value, ValueType};
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
@@ -17,6 +17,14 @@ pub type PartitionKey = Arc<str>;
 pub struct Batch {
     pub(crate) data: Vec<Item>,
     keyspace: Keyspace,
+
+    /// Seqno to commit the batch with, instead of generating a new one
+    ///
+    /// Used to apply replicated batches.
+    pub(crate) seqno: Option<SeqNo>,
+
+    /// Item counts at each savepoint
+    savepoints: Vec<usize>,
 }
 
 impl Batch {
@@ -32,6 +40,8 @@ impl Batch {
         Self {
             data: Vec::with_capacity(capacity),
             keyspace,
+            seqno: None,
+            savepoints: Vec::new(),
         }
     }
 
@@ -60,12 +70,132 @@ impl Batch {
         ));
     }
 
+    /// Returns the amount of staged items.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no items are staged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the size of all staged keys and values in bytes.
+    #[must_use]
+    pub fn size_bytes(&self) -> u64 {
+        self.data
+            .iter()
+            .map(|item| (item.key.len() + item.value.len()) as u64)
+            .sum()
+    }
+
+    /// Removes all staged items and savepoints, but keeps the allocated capacity.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.savepoints.clear();
+    }
+
+    /// Marks the current state of the batch, so later items can be undone
+    /// using [`Batch::rollback_to_savepoint`].
+    ///
+    /// Savepoints can be nested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let mut batch = keyspace.batch();
+    /// batch.insert(&partition, "a", "abc");
+    ///
+    /// batch.set_savepoint();
+    /// batch.insert(&partition, "b", "abc");
+    /// batch.remove(&partition, "a");
+    /// assert!(batch.rollback_to_savepoint());
+    ///
+    /// batch.commit()?;
+    /// assert!(partition.contains_key("a")?);
+    /// assert!(!partition.contains_key("b")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    pub fn set_savepoint(&mut self) {
+        self.savepoints.push(self.data.len());
+    }
+
+    /// Removes all items that were staged since the most recent savepoint, and removes the savepoint.
+    ///
+    /// Returns `false` if there is no savepoint.
+    pub fn rollback_to_savepoint(&mut self) -> bool {
+        let Some(len) = self.savepoints.pop() else {
+            return false;
+        };
+
+        self.data.truncate(len);
+        true
+    }
+
+    /// Removes the most recent savepoint, keeping all items.
+    ///
+    /// Returns `false` if there is no savepoint.
+    pub fn pop_savepoint(&mut self) -> bool {
+        self.savepoints.pop().is_some()
+    }
+
     /// Commits the batch to the [`Keyspace`] atomically
     ///
+    /// After committing, the batch is empty, but keeps its allocated capacity,
+    /// so it can be reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let mut batch = keyspace.batch_with_capacity(100);
+    ///
+    /// for x in 0..10_u64 {
+    ///     batch.insert(&partition, x.to_be_bytes(), "abc");
+    ///     batch.insert(&partition, (x + 100).to_be_bytes(), "abc");
+    ///     assert_eq!(2, batch.len());
+    ///
+    ///     batch.commit()?;
+    ///     assert!(batch.is_empty());
+    /// }
+    ///
+    /// assert_eq!(20, partition.len()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, if any key or value is too large,
+    /// or if any item is rejected by a [`crate::WriteInterceptor`].
+    /// In that case, nothing is written, and the items stay staged, so the commit can be retried.
+    pub fn commit(&mut self) -> crate::Result<()> {
+        self.commit_with_seqno()?;
+        Ok(())
+    }
+
+    /// Commits the batch to the [`Keyspace`] atomically, like [`Batch::commit`].
+    ///
+    /// Returns the seqno that was assigned to all items of the batch.
+    ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
-    pub fn commit(mut self) -> crate::Result<()> {
+    /// Will return `Err` if an IO error occurs, if any key or value is too large,
+    /// or if any item is rejected by a [`crate::WriteInterceptor`].
+    /// In that case, nothing is written, and the items stay staged, so the commit can be retried.
+    pub fn commit_with_seqno(&mut self) -> crate::Result<SeqNo> {
         if self
             .keyspace
             .is_poisoned
@@ -74,6 +204,35 @@ impl Batch {
             return Err(crate::Error::Poisoned);
         }
 
+        // IMPORTANT: Check sizes (and ask interceptors) before touching the journal
+        // so a bad item cannot leave a partially written batch behind
+        {
+            // NOTE: Clone the handles, so interceptors don't run while holding the partitions lock
+            let handles = {
+                let partitions = self.keyspace.partitions.read().expect("lock is poisoned");
+
+                let mut handles = HashMap::new();
+
+                for item in &self.data {
+                    if let Some(partition) = partitions.get(&item.partition) {
+                        handles
+                            .entry(item.partition.clone())
+                            .or_insert_with(|| partition.clone());
+                    }
+                }
+
+                handles
+            };
+
+            for item in &self.data {
+                item.check_size()?;
+
+                if let Some(partition) = handles.get(&item.partition) {
+                    partition.intercept_write(&item.key, item.value.len(), item.value_type)?;
+                }
+            }
+        }
+
         log::trace!("batch: Acquiring shard");
         let mut shard = self.keyspace.journal.get_writer();
 
@@ -112,18 +271,36 @@ impl Batch {
             lock_map
         };
 
-        let batch_seqno = self.keyspace.seqno.next();
+        // IMPORTANT: The seqno is allocated while holding the journal shard,
+        // and the batch only becomes visible to snapshots once all batches
+        // with lower seqnos are applied as well
+        let ticket = if let Some(seqno) = self.seqno {
+            self.keyspace.commit_pipeline.begin_at(seqno)
+        } else {
+            self.keyspace.commit_pipeline.begin()
+        };
+        let batch_seqno = ticket.seqno();
 
         let items = self.data.iter().collect::<Vec<_>>();
         let _ = shard.writer.write_batch(&items, batch_seqno)?;
 
+        fail_point!(self.keyspace.config, "journal::after_write");
+
         #[allow(clippy::mutable_key_type)]
         let mut partitions_with_possible_stall = HashSet::new();
 
         let mut batch_size = 0u64;
 
+        let mut events = vec![];
+
         log::trace!("Applying {} batched items to memtable(s)", self.data.len());
-        for item in std::mem::take(&mut self.data) {
+
+        // NOTE: Only reset once the batch is persisted, so a failed commit can be retried
+        // with the same replicated seqno
+        self.seqno = None;
+        self.savepoints.clear();
+
+        for item in self.data.drain(..) {
             let Some(partition) = partitions.get(&item.partition) else {
                 continue;
             };
@@ -132,6 +309,18 @@ impl Batch {
                 continue;
             };
 
+            if partition.watchers.is_active() {
+                events.push((
+                    partition.clone(),
+                    WatchEvent::new(
+                        item.key.clone(),
+                        item.value.clone(),
+                        item.value_type,
+                        batch_seqno,
+                    ),
+                ));
+            }
+
             let value = Value {
                 key: item.key,
                 value: item.value,
@@ -147,9 +336,14 @@ impl Batch {
         }
 
         drop(locked_memtables);
+        drop(ticket);
         drop(partitions);
         drop(shard);
 
+        for (partition, event) in events {
+            partition.watchers.notify(&[event]);
+        }
+
         // IMPORTANT: Add batch size to current write buffer size
         // Otherwise write buffer growth is unbounded when using batches
         self.keyspace.write_buffer_manager.allocate(batch_size);
@@ -168,6 +362,6 @@ impl Batch {
             partition.check_write_buffer_size(write_buffer_size);
         }
 
-        Ok(())
+        Ok(batch_seqno)
     }
 }
@@ -0,0 +1,204 @@
+//! Command line tool to inspect and maintain a keyspace
+//!
+//! Install using `cargo install fjall --features cli`.
+//!
+//! Opening a keyspace runs journal recovery, which may truncate, seal or delete journals.
+//! So `inspect`, `dump` and `verify` open a temporary copy of the keyspace instead,
+//! which leaves the keyspace itself untouched.
+//!
+//! `compact` opens the keyspace itself, so **never run it against a keyspace
+//! that is opened by another process**.
+//!
+//! NOTE: There are no `dump-segment` and `repair-manifest` commands,
+//! because segment files and the level manifest are internals of `lsm-tree`,
+//! which fjall does not expose.
+
+use fjall::{Config, Keyspace, PartitionCreateOptions};
+use std::{path::Path, process::ExitCode};
+use tempfile::TempDir;
+
+const USAGE: &str = "Usage: fjall <command> <keyspace folder> [partition]
+
+Commands:
+  inspect <folder>              Lists partitions and their stats
+  dump <folder> <partition>     Prints all items of a partition
+  verify <folder>               Scans all partitions, reporting read errors
+  compact <folder>              Flushes all memtables and runs pending compactions
+
+inspect, dump and verify work on a temporary copy of the keyspace, so they never modify it.
+
+WARNING: Never run compact against a keyspace that is opened by another process.";
+
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|&b| std::ascii::escape_default(b))
+        .map(char::from)
+        .collect()
+}
+
+fn check_keyspace(folder: &str) -> fjall::Result<()> {
+    if !Path::new(folder).join("version").try_exists()? {
+        return Err(fjall::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{folder:?} is not a keyspace"),
+        )));
+    }
+
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for dirent in std::fs::read_dir(from)? {
+        let dirent = dirent?;
+        let path = to.join(dirent.file_name());
+
+        if dirent.file_type()?.is_dir() {
+            copy_dir(&dirent.path(), &path)?;
+        } else {
+            std::fs::copy(dirent.path(), path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens a temporary copy of the keyspace, so recovery cannot modify the keyspace itself
+///
+/// The copy is deleted when the returned [`TempDir`] is dropped,
+/// which needs to happen after the keyspace is dropped.
+fn open_copy(folder: &str) -> fjall::Result<(TempDir, Keyspace)> {
+    check_keyspace(folder)?;
+
+    let copy = tempfile::tempdir()?;
+    copy_dir(Path::new(folder), copy.path())?;
+
+    // NOTE: Don't flush or compact, there is no point in rewriting the copy
+    let keyspace = Config::new(copy.path()).background_threads(false).open()?;
+
+    Ok((copy, keyspace))
+}
+
+fn inspect(keyspace: &Keyspace) -> fjall::Result<()> {
+    println!("journals: {}", keyspace.journal_count());
+    println!("disk space: {} bytes", keyspace.disk_space());
+    println!("partitions: {}", keyspace.partition_count());
+
+    let mut names = keyspace.list_partitions();
+    names.sort();
+
+    for name in names {
+        let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+
+        println!(
+            "  {name}: ~{} items, {} segments, {} bytes",
+            partition.approximate_len(),
+            partition.segment_count(),
+            partition.disk_space(),
+        );
+    }
+
+    Ok(())
+}
+
+fn dump(keyspace: &Keyspace, name: &str) -> fjall::Result<()> {
+    if !keyspace.partition_exists(name) {
+        return Err(fjall::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("partition {name:?} does not exist"),
+        )));
+    }
+
+    let partition = keyspace.open_partition(name, PartitionCreateOptions::default())?;
+
+    for item in partition.iter() {
+        let (key, value) = item?;
+        println!("{}\t{}", escape(&key), escape(&value));
+    }
+
+    Ok(())
+}
+
+fn verify(keyspace: &Keyspace) -> fjall::Result<bool> {
+    let mut is_ok = true;
+
+    for name in keyspace.list_partitions() {
+        let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+
+        let mut count = 0;
+        let mut errors = 0;
+
+        for item in partition.iter() {
+            match item {
+                Ok(_) => count += 1,
+                Err(e) => {
+                    eprintln!("{name}: {e}");
+                    errors += 1;
+                }
+            }
+        }
+
+        println!("{name}: {count} items, {errors} errors");
+        is_ok &= errors == 0;
+    }
+
+    Ok(is_ok)
+}
+
+fn compact(folder: &str) -> fjall::Result<()> {
+    check_keyspace(folder)?;
+
+    // NOTE: Run maintenance in this thread, so we know when it is done
+    let keyspace = Config::new(folder).background_threads(false).open()?;
+
+    for name in keyspace.list_partitions() {
+        let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+        partition.rotate_memtable()?;
+    }
+
+    keyspace.maintenance();
+
+    for name in keyspace.list_partitions() {
+        let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+        println!("{name}: {} segments", partition.segment_count());
+    }
+
+    Ok(())
+}
+
+fn run(args: &[String]) -> fjall::Result<bool> {
+    match args {
+        [cmd, folder] if cmd == "inspect" => {
+            let (_copy, keyspace) = open_copy(folder)?;
+            inspect(&keyspace).map(|()| true)
+        }
+        [cmd, folder, partition] if cmd == "dump" => {
+            let (_copy, keyspace) = open_copy(folder)?;
+            dump(&keyspace, partition).map(|()| true)
+        }
+        [cmd, folder] if cmd == "verify" => {
+            let (_copy, keyspace) = open_copy(folder)?;
+            verify(&keyspace)
+        }
+        [cmd, folder] if cmd == "compact" => compact(folder).map(|()| true),
+        _ => {
+            eprintln!("{USAGE}");
+            Ok(false)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use lsm_tree::{SeqNo, SequenceNumberCounter};
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+
+struct CommitPipelineInner {
+    seqno: SequenceNumberCounter,
+
+    /// Seqnos that were handed out, but whose writes are not yet fully applied
+    in_flight: Mutex<BTreeSet<SeqNo>>,
+}
+
+/// Hands out seqnos to writers, and keeps track of which writes are still in flight
+///
+/// Writes to different journal shards run concurrently, so a write may be
+/// applied to the memtables before another write with a lower seqno.
+/// Snapshots are only ever taken at the visible seqno, which is the lowest seqno
+/// that is still in flight, so a snapshot never observes a write without all
+/// writes that were committed before it.
+#[derive(Clone)]
+pub struct CommitPipeline(Arc<CommitPipelineInner>);
+
+impl CommitPipeline {
+    pub fn new(seqno: SequenceNumberCounter) -> Self {
+        Self(Arc::new(CommitPipelineInner {
+            seqno,
+            in_flight: Mutex::default(),
+        }))
+    }
+
+    /// Allocates the next seqno.
+    ///
+    /// The write becomes visible to snapshots once the returned ticket, and all tickets
+    /// with a lower seqno, are dropped.
+    pub fn begin(&self) -> CommitTicket {
+        let mut in_flight = self.0.in_flight.lock().expect("lock is poisoned");
+
+        let seqno = self.0.seqno.next();
+        in_flight.insert(seqno);
+
+        CommitTicket {
+            pipeline: self.clone(),
+            seqno,
+        }
+    }
+
+    /// Registers a write with a given seqno, e.g. from another keyspace.
+    ///
+    /// Locally allocated seqnos will never go below the given one.
+    pub fn begin_at(&self, seqno: SeqNo) -> CommitTicket {
+        let mut in_flight = self.0.in_flight.lock().expect("lock is poisoned");
+
+        self.0
+            .seqno
+            .fetch_max(seqno + 1, std::sync::atomic::Ordering::AcqRel);
+        in_flight.insert(seqno);
+
+        CommitTicket {
+            pipeline: self.clone(),
+            seqno,
+        }
+    }
+
+    /// Returns the seqno up to which (exclusive) all writes are visible.
+    pub fn visible_seqno(&self) -> SeqNo {
+        let in_flight = self.0.in_flight.lock().expect("lock is poisoned");
+
+        // NOTE: Seqnos are only allocated while holding the lock,
+        // so every seqno below the counter is either in flight, or applied
+        in_flight
+            .first()
+            .copied()
+            .unwrap_or_else(|| self.0.seqno.get())
+    }
+}
+
+/// A seqno that was allocated by the [`CommitPipeline`]
+///
+/// Dropping the ticket marks the write as applied.
+#[must_use]
+pub struct CommitTicket {
+    pipeline: CommitPipeline,
+    seqno: SeqNo,
+}
+
+impl CommitTicket {
+    pub fn seqno(&self) -> SeqNo {
+        self.seqno
+    }
+}
+
+impl Drop for CommitTicket {
+    fn drop(&mut self) {
+        self.pipeline
+            .0
+            .in_flight
+            .lock()
+            .expect("lock is poisoned")
+            .remove(&self.seqno);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn commit_pipeline_visible_seqno() {
+        let pipeline = CommitPipeline::new(SequenceNumberCounter::default());
+        assert_eq!(0, pipeline.visible_seqno());
+
+        let a = pipeline.begin();
+        let b = pipeline.begin();
+        assert_eq!(0, a.seqno());
+        assert_eq!(1, b.seqno());
+        assert_eq!(0, pipeline.visible_seqno());
+
+        // NOTE: b is applied, but a is still in flight, so b must not be visible yet
+        drop(b);
+        assert_eq!(0, pipeline.visible_seqno());
+
+        drop(a);
+        assert_eq!(2, pipeline.visible_seqno());
+
+        let c = pipeline.begin_at(10);
+        assert_eq!(10, c.seqno());
+        assert_eq!(10, pipeline.visible_seqno());
+        drop(c);
+
+        assert_eq!(11, pipeline.visible_seqno());
+        assert_eq!(11, pipeline.begin().seqno());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::PartitionHandle;
+use crate::{event::CompactionReason, PartitionHandle};
 use std::{
     collections::VecDeque,
     sync::{Arc, Mutex},
@@ -6,7 +6,7 @@ use std::{
 use std_semaphore::Semaphore;
 
 pub struct CompactionManagerInner {
-    partitions: Mutex<VecDeque<PartitionHandle>>,
+    partitions: Mutex<VecDeque<(PartitionHandle, CompactionReason)>>,
     semaphore: Semaphore,
 }
 
@@ -47,16 +47,16 @@ impl std::ops::Deref for CompactionManager {
 impl CompactionManager {
     pub fn remove_partition(&self, name: &str) {
         let mut lock = self.partitions.lock().expect("lock is poisoned");
-        lock.retain(|x| &*x.name != name);
+        lock.retain(|(x, _)| &*x.name != name);
     }
 
     pub fn wait_for(&self) {
         self.semaphore.acquire();
     }
 
-    pub fn notify(&self, partition: PartitionHandle) {
+    pub fn notify(&self, partition: PartitionHandle, reason: CompactionReason) {
         let mut lock = self.partitions.lock().expect("lock is poisoned");
-        lock.push_back(partition);
+        lock.push_back((partition, reason));
         self.semaphore.release();
     }
 
@@ -64,7 +64,7 @@ impl CompactionManager {
         self.semaphore.release();
     }
 
-    pub fn pop(&self) -> Option<PartitionHandle> {
+    pub fn pop(&self) -> Option<(PartitionHandle, CompactionReason)> {
         let mut lock = self.partitions.lock().expect("lock is poisoned");
         lock.pop_front()
     }
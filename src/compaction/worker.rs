@@ -1,11 +1,27 @@
 use super::manager::CompactionManager;
+use crate::{
+    event::{CompactionReason, Event},
+    slow_op::SlowOp,
+    PartitionHandle,
+};
 
 /// Runs a single run of compaction.
 pub fn run(compaction_manager: &CompactionManager) {
-    let Some(item) = compaction_manager.pop() else {
+    let Some((item, reason)) = compaction_manager.pop() else {
         return;
     };
 
+    compact(&item, reason);
+}
+
+/// Runs compactions until no partition is queued anymore.
+pub fn run_until_empty(compaction_manager: &CompactionManager) {
+    while let Some((item, reason)) = compaction_manager.pop() {
+        compact(&item, reason);
+    }
+}
+
+fn compact(item: &PartitionHandle, reason: CompactionReason) {
     log::trace!(
         "compactor: calling compaction strategy for partition {:?}",
         item.0.name
@@ -18,7 +34,29 @@ pub fn run(compaction_manager: &CompactionManager) {
 
     // TODO: loop if there's more work to do
 
-    if let Err(e) = item.tree.compact(strategy) {
+    item.events.emit(|| Event::CompactionStarted {
+        partition: item.name.clone(),
+        reason,
+    });
+
+    let start = std::time::Instant::now();
+
+    let result = item.tree.compact(strategy);
+
+    if let Err(e) = &result {
         log::error!("Compaction failed: {e:?}");
-    };
+    }
+
+    item.events.emit(|| Event::CompactionFinished {
+        partition: item.name.clone(),
+        duration: start.elapsed(),
+        error: result.as_ref().err().map(|e| format!("{e:?}")),
+    });
+
+    if let Some(observer) = &item.keyspace_config.slow_op_observer {
+        observer.observe(start.elapsed(), |duration| SlowOp::Compaction {
+            partition: item.name.clone(),
+            duration,
+        });
+    }
 }
@@ -1,8 +1,14 @@
-use crate::{journal::shard::RecoveryMode, path::absolute_path, Keyspace};
+use crate::{
+    journal::shard::RecoveryMode,
+    path::absolute_path,
+    slow_op::{SlowOp, SlowOpObserver},
+    Keyspace,
+};
 use lsm_tree::{descriptor_table::FileDescriptorTable, BlockCache};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 /// Global keyspace configuration
@@ -39,8 +45,27 @@ pub struct Config {
     pub(crate) fsync_ms: Option<u16>,
 
     pub(crate) journal_recovery_mode: RecoveryMode,
+
+    /// If false, no background threads are started
+    pub(crate) background_threads: bool,
+
+    /// If true, sealed journals found during recovery are flushed together
+    pub(crate) merge_recovered_journals: bool,
+
+    /// Callback for operations that exceed a duration
+    pub(crate) slow_op_observer: Option<SlowOpObserver>,
+
+    /// Checks keys of all writes
+    pub(crate) key_validator: Option<KeyValidator>,
+
+    /// Armed failpoints of this keyspace
+    #[cfg(feature = "failpoints")]
+    pub(crate) failpoints: crate::failpoints::Failpoints,
 }
 
+type KeyValidator =
+    Arc<dyn Fn(&str, &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync>;
+
 const DEFAULT_CPU_CORES: usize = 4;
 
 fn get_open_file_limit() -> usize {
@@ -74,6 +99,12 @@ impl Default for Config {
             flush_workers_count: cpus,
             compaction_workers_count: cpus,
             journal_recovery_mode: RecoveryMode::default(),
+            background_threads: true,
+            merge_recovered_journals: true,
+            slow_op_observer: None,
+            key_validator: None,
+            #[cfg(feature = "failpoints")]
+            failpoints: crate::failpoints::Failpoints::default(),
         }
     }
 }
@@ -185,6 +216,137 @@ impl Config {
         self
     }
 
+    /// Sets how strict journal recovery is, when a corrupt or
+    /// incomplete batch is found in a journal.
+    ///
+    /// Default = [`RecoveryMode::TolerateCorruptTail`]
+    #[must_use]
+    pub fn journal_recovery_mode(mut self, mode: RecoveryMode) -> Self {
+        self.journal_recovery_mode = mode;
+        self
+    }
+
+    /// If false, the keyspace does not start any background threads.
+    ///
+    /// Flushes and compactions then run inline, when writes need them
+    /// or when [`Keyspace::maintenance`] is called, which should be done periodically.
+    /// The journal is also not fsynced asynchronously, so use [`Keyspace::persist`] as needed.
+    ///
+    /// Useful for constrained environments that cannot spawn threads.
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn background_threads(mut self, flag: bool) -> Self {
+        self.background_threads = flag;
+        self
+    }
+
+    /// If true, the data of all sealed journals that are found during recovery
+    /// is merged into a single memtable per partition, so it is flushed into
+    /// one segment, instead of one segment per journal.
+    ///
+    /// Otherwise, recovering after repeated crashes may create many small L0 segments.
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn merge_recovered_journals(mut self, flag: bool) -> Self {
+        self.merge_recovered_journals = flag;
+        self
+    }
+
+    /// Invokes the callback for every point read, scan, flush or compaction
+    /// that takes at least `threshold`.
+    ///
+    /// The callback runs in the thread that performed the operation, so it should return quickly.
+    ///
+    /// Default = disabled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions, SlowOp};
+    /// # use std::time::Duration;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder)
+    ///     .slow_op_threshold(Duration::from_millis(100), |op| {
+    ///         if let SlowOp::Get { partition, duration, .. } = op {
+    ///             eprintln!("slow read in {partition}: {duration:?}");
+    ///         }
+    ///     })
+    ///     .open()?;
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn slow_op_threshold<F: Fn(&SlowOp) + Send + Sync + 'static>(
+        mut self,
+        threshold: Duration,
+        callback: F,
+    ) -> Self {
+        self.slow_op_observer = Some(SlowOpObserver::new(threshold, Arc::new(callback)));
+        self
+    }
+
+    /// Sets a validator that checks the key of every insert and remove,
+    /// in all partitions, including batches and transactions.
+    ///
+    /// The validator receives the partition name and the key. If it returns `Err`,
+    /// the write is rejected with an [`crate::Error::Io`] of kind
+    /// [`std::io::ErrorKind::InvalidInput`] before it is appended to the journal,
+    /// so malformed keys never end up in the sort order.
+    ///
+    /// Default = None
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder)
+    ///     .key_validator(|_partition, key| {
+    ///         if !key.starts_with(b"user#") {
+    ///             return Err("key should start with user#".into());
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .open()?;
+    /// let users = keyspace.open_partition("users", PartitionCreateOptions::default())?;
+    ///
+    /// users.insert("user#1", "abc")?;
+    /// assert!(matches!(
+    ///     users.insert("1", "abc"),
+    ///     Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput,
+    /// ));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn key_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str, &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.key_validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Sets the failpoints of the keyspace, see [`crate::failpoints`].
+    ///
+    /// Failpoints are scoped to the keyspace, so arming them
+    /// does not affect any other keyspace in the process.
+    ///
+    /// Default = no failpoints armed
+    #[cfg(feature = "failpoints")]
+    #[must_use]
+    pub fn failpoints(mut self, failpoints: crate::failpoints::Failpoints) -> Self {
+        self.failpoints = failpoints;
+        self
+    }
+
     /// Opens a keyspace using the config.
     ///
     /// # Errors
@@ -0,0 +1,182 @@
+use lsm_tree::{KvPair, UserKey, UserValue};
+use std::{cmp::Ordering, iter::Peekable};
+
+/// A difference between two sorted key-value sequences
+///
+/// See [`diff`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DiffEntry {
+    /// Key only exists in the right side
+    Added(UserKey, UserValue),
+
+    /// Key only exists in the left side
+    Removed(UserKey, UserValue),
+
+    /// Key exists in both sides, but with different values
+    Changed {
+        /// Key
+        key: UserKey,
+
+        /// Value in the left side
+        old: UserValue,
+
+        /// Value in the right side
+        new: UserValue,
+    },
+}
+
+impl DiffEntry {
+    /// Returns the key of the entry.
+    #[must_use]
+    pub fn key(&self) -> &UserKey {
+        match self {
+            Self::Added(key, _) | Self::Removed(key, _) | Self::Changed { key, .. } => key,
+        }
+    }
+}
+
+/// Iterator over the differences of two sorted key-value sequences
+///
+/// See [`diff`].
+pub struct Diff<A: Iterator, B: Iterator> {
+    left: Peekable<A>,
+    right: Peekable<B>,
+}
+
+/// Walks two sorted key-value sequences in lockstep, and yields all keys that were added,
+/// removed or changed going from `left` to `right`.
+///
+/// Both sides are usually partitions or snapshots, see [`crate::PartitionHandle::diff`].
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, DiffEntry, PartitionCreateOptions};
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let keyspace = Config::new(folder).open()?;
+/// let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+/// partition.insert("a", "abc")?;
+/// partition.insert("b", "def")?;
+///
+/// let before = partition.snapshot();
+///
+/// partition.insert("a", "xyz")?;
+/// partition.remove("b")?;
+/// partition.insert("c", "ghi")?;
+///
+/// let changes = fjall::diff(before.iter(), partition.iter())
+///     .collect::<fjall::Result<Vec<_>>>()?;
+///
+/// assert_eq!(3, changes.len());
+/// assert!(matches!(changes[0], DiffEntry::Changed { .. }));
+/// assert!(matches!(changes[1], DiffEntry::Removed(..)));
+/// assert!(matches!(changes[2], DiffEntry::Added(..)));
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+pub fn diff<A, B, E1, E2>(left: A, right: B) -> Diff<A::IntoIter, B::IntoIter>
+where
+    A: IntoIterator<Item = Result<KvPair, E1>>,
+    B: IntoIterator<Item = Result<KvPair, E2>>,
+    crate::Error: From<E1> + From<E2>,
+{
+    Diff {
+        left: left.into_iter().peekable(),
+        right: right.into_iter().peekable(),
+    }
+}
+
+/// Takes the next item of an iterator, converting its error
+fn take_next<I, E>(iter: &mut Peekable<I>) -> Option<crate::Result<KvPair>>
+where
+    I: Iterator<Item = Result<KvPair, E>>,
+    crate::Error: From<E>,
+{
+    iter.next().map(|item| item.map_err(Into::into))
+}
+
+impl<A, B, E1, E2> Iterator for Diff<A, B>
+where
+    A: Iterator<Item = Result<KvPair, E1>>,
+    B: Iterator<Item = Result<KvPair, E2>>,
+    crate::Error: From<E1> + From<E2>,
+{
+    type Item = crate::Result<DiffEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // NOTE: Errors are yielded as soon as they are peeked
+            let ordering = match (self.left.peek(), self.right.peek()) {
+                (None, None) => return None,
+                (Some(Err(_)), _) | (Some(_), None) => Ordering::Less,
+                (_, Some(Err(_))) | (None, Some(_)) => Ordering::Greater,
+                (Some(Ok((left_key, _))), Some(Ok((right_key, _)))) => left_key.cmp(right_key),
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    return Some(
+                        take_next(&mut self.left)?
+                            .map(|(key, value)| DiffEntry::Removed(key, value)),
+                    );
+                }
+                Ordering::Greater => {
+                    return Some(
+                        take_next(&mut self.right)?
+                            .map(|(key, value)| DiffEntry::Added(key, value)),
+                    );
+                }
+                Ordering::Equal => {
+                    let (key, old) = match take_next(&mut self.left)? {
+                        Ok(kv) => kv,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    let (_, new) = match take_next(&mut self.right)? {
+                        Ok(kv) => kv,
+                        Err(e) => return Some(Err(e)),
+                    };
+
+                    if old != new {
+                        return Some(Ok(DiffEntry::Changed { key, old, new }));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn kv(key: &str, value: &str) -> crate::Result<KvPair> {
+        Ok((key.as_bytes().into(), value.as_bytes().into()))
+    }
+
+    #[test]
+    fn diff_sorted_sequences() -> crate::Result<()> {
+        let left = vec![kv("a", "1"), kv("b", "2"), kv("d", "4"), kv("e", "5")];
+        let right = vec![kv("b", "2"), kv("c", "3"), kv("d", "x"), kv("f", "6")];
+
+        let keys = diff(left, right)
+            .map(|entry| entry.map(|entry| entry.key().to_vec()))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        assert_eq!(
+            vec![
+                b"a".to_vec(),
+                b"c".to_vec(),
+                b"d".to_vec(),
+                b"e".to_vec(),
+                b"f".to_vec()
+            ],
+            keys,
+        );
+
+        assert_eq!(0, diff(vec![kv("a", "1")], vec![kv("a", "1")]).count());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use crate::batch::PartitionKey;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// Why writes are being slowed down or blocked
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StallReason {
+    /// Journals are (close to) exceeding their maximum size
+    JournalSize,
+
+    /// Write buffer is (close to) exceeding its maximum size
+    WriteBufferSize,
+
+    /// There are too many segments in L0
+    L0Segments,
+}
+
+/// Why a partition was handed to its compaction strategy
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CompactionReason {
+    /// A memtable was flushed into a new segment
+    Flush,
+
+    /// Writes are stalled or halted because there are too many segments in L0
+    L0Segments,
+}
+
+/// A background event of a keyspace
+///
+/// See [`crate::Keyspace::events`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// A sealed memtable is being flushed
+    FlushStarted {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Size of the memtable in bytes
+        memtable_size: u32,
+    },
+
+    /// A flush is done, successfully or not
+    ///
+    /// Every [`Event::FlushStarted`] is followed by exactly one `FlushFinished`.
+    FlushFinished {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Size of the memtable in bytes
+        memtable_size: u32,
+
+        /// Time spent
+        duration: Duration,
+
+        /// Error message, if the flush failed
+        error: Option<String>,
+    },
+
+    /// A partition was handed to its compaction strategy
+    ///
+    /// The strategy may decide there is nothing to compact, so a
+    /// start/finish pair does not necessarily mean any data was rewritten.
+    ///
+    /// NOTE: The strategy's own decision is made inside the LSM-tree,
+    /// which does not report it back, so only the reason the partition
+    /// was picked can be exposed.
+    CompactionStarted {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Reason
+        reason: CompactionReason,
+    },
+
+    /// A compaction is done, successfully or not
+    CompactionFinished {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Time spent
+        duration: Duration,
+
+        /// Error message, if the compaction failed
+        error: Option<String>,
+    },
+
+    /// Writes to a partition started getting stalled or halted
+    WriteStallStarted {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Reason
+        reason: StallReason,
+    },
+
+    /// Writes to a partition are not stalled anymore
+    WriteStallFinished {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Reason
+        reason: StallReason,
+
+        /// Time spent stalling
+        duration: Duration,
+    },
+}
+
+#[derive(Default)]
+struct EventBusInner {
+    /// Fast path, so events are not even built if nobody is listening
+    is_active: AtomicBool,
+
+    subscribers: Mutex<Vec<Sender<Event>>>,
+}
+
+/// Sends background events to all subscribers
+#[derive(Clone, Default)]
+pub struct EventBus(Arc<EventBusInner>);
+
+impl EventBus {
+    pub fn subscribe(&self) -> Receiver<Event> {
+        let (tx, rx) = channel();
+
+        let mut subscribers = self.0.subscribers.lock().expect("lock is poisoned");
+        subscribers.push(tx);
+        self.0.is_active.store(true, Ordering::Release);
+
+        rx
+    }
+
+    /// Sends an event to all subscribers.
+    ///
+    /// Subscribers whose receiver was dropped are removed.
+    pub fn emit<F: FnOnce() -> Event>(&self, f: F) {
+        if !self.0.is_active.load(Ordering::Acquire) {
+            return;
+        }
+
+        let event = f();
+
+        let mut subscribers = self.0.subscribers.lock().expect("lock is poisoned");
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+
+        self.0
+            .is_active
+            .store(!subscribers.is_empty(), Ordering::Release);
+    }
+}
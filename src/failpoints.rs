@@ -0,0 +1,95 @@
+//! Fault injection hooks for crash-consistency testing.
+//!
+//! Failpoints are named places in the write, rotation and flush paths.
+//! When a failpoint is armed, reaching it either returns an error or panics
+//! (simulating a crash), so applications can test how they recover.
+//!
+//! Available failpoints:
+//!
+//! - `journal::after_write`: after a write (or batch) was appended to the journal, before it is applied to the memtable.
+//!   This simulates "write persisted, ack lost": with [`Action::Error`], the caller sees an error
+//!   and the write is not visible, but it reappears once the journal is recovered
+//! - `journal::before_rotate`: after a memtable was sealed, before the journal is rotated
+//! - `flush::before_register`: after a segment was written, before it is registered in the tree
+//!
+//! Failpoints are armed per keyspace, see [`crate::Config::failpoints`],
+//! so other keyspaces in the same process (e.g. in concurrently running tests) are not affected.
+//!
+//! # Examples
+//!
+//! ```
+//! # use fjall::{Config, PartitionCreateOptions};
+//! use fjall::failpoints::{Action, Failpoints};
+//! #
+//! # let folder = tempfile::tempdir()?;
+//! let failpoints = Failpoints::default();
+//! let keyspace = Config::new(folder).failpoints(failpoints.clone()).open()?;
+//! # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+//!
+//! failpoints.set("journal::after_write", Action::Error);
+//! assert!(partition.insert("a", "abc").is_err());
+//!
+//! failpoints.remove("journal::after_write");
+//! partition.insert("a", "abc")?;
+//! #
+//! # Ok::<(), fjall::Error>(())
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// What happens when an armed failpoint is reached
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Return an I/O error
+    Error,
+
+    /// Panic, simulating a crash
+    Panic,
+}
+
+/// A set of armed failpoints
+///
+/// Cloning is cheap, and all clones share the same failpoints.
+#[derive(Clone, Debug, Default)]
+pub struct Failpoints(Arc<Mutex<HashMap<String, Action>>>);
+
+impl Failpoints {
+    /// Arms a failpoint.
+    pub fn set(&self, name: &str, action: Action) {
+        self.0
+            .lock()
+            .expect("lock is poisoned")
+            .insert(name.into(), action);
+    }
+
+    /// Disarms a failpoint.
+    pub fn remove(&self, name: &str) {
+        self.0.lock().expect("lock is poisoned").remove(name);
+    }
+
+    /// Disarms all failpoints.
+    pub fn clear(&self) {
+        self.0.lock().expect("lock is poisoned").clear();
+    }
+
+    /// Triggers the failpoint if it is armed.
+    pub(crate) fn eval(&self, name: &str) -> crate::Result<()> {
+        let action = self.0.lock().expect("lock is poisoned").get(name).copied();
+
+        match action {
+            Some(Action::Error) => {
+                log::warn!("failpoint {name:?} triggered");
+
+                Err(crate::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("failpoint {name:?} triggered"),
+                )))
+            }
+            Some(Action::Panic) => panic!("failpoint {name:?} triggered"),
+            None => Ok(()),
+        }
+    }
+}
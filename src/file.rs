@@ -4,7 +4,9 @@ pub const JOURNALS_FOLDER: &str = "journals";
 pub const SEGMENTS_FOLDER: &str = "segments";
 pub const PARTITIONS_FOLDER: &str = "partitions";
 pub const FJALL_MARKER: &str = "version";
+pub const REPLICATION_MARKER: &str = "replicated";
 pub const PARTITION_DELETED_MARKER: &str = ".deleted";
+pub const SHARD_MARKER: &str = ".shard";
 
 pub const FLUSH_PARTITIONS_LIST: &str = ".partitions";
 pub const FLUSH_MARKER: &str = ".flush";
@@ -21,3 +23,30 @@ pub fn fsync_directory<P: AsRef<Path>>(_path: P) -> std::io::Result<()> {
     // Cannot fsync directory on Windows
     Ok(())
 }
+
+/// Atomically replaces the file at `to` with the file at `from`, and persists the rename.
+#[cfg(not(target_os = "windows"))]
+pub fn rename_atomic<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> std::io::Result<()> {
+    let to = to.as_ref();
+
+    std::fs::rename(from, to)?;
+
+    // IMPORTANT: fsync folder, so the new directory entry is persisted
+    if let Some(folder) = to.parent() {
+        fsync_directory(folder)?;
+    }
+
+    Ok(())
+}
+
+/// Atomically replaces the file at `to` with the file at `from`, and persists the rename.
+#[cfg(target_os = "windows")]
+pub fn rename_atomic<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> std::io::Result<()> {
+    let to = to.as_ref();
+
+    // NOTE: std::fs::rename uses MoveFileExW with MOVEFILE_REPLACE_EXISTING, but without
+    // MOVEFILE_WRITE_THROUGH, and directories cannot be fsynced on Windows.
+    // Instead, flush the renamed file (FlushFileBuffers), which also flushes its metadata
+    std::fs::rename(from, to)?;
+    std::fs::OpenOptions::new().write(true).open(to)?.sync_all()
+}
@@ -1,7 +1,13 @@
 use super::manager::{FlushManager, Task};
 use crate::{
-    batch::PartitionKey, compaction::manager::CompactionManager, file::SEGMENTS_FOLDER,
-    journal::manager::JournalManager, write_buffer_manager::WriteBufferManager, PartitionHandle,
+    batch::PartitionKey,
+    compaction::manager::CompactionManager,
+    event::{CompactionReason, Event},
+    file::SEGMENTS_FOLDER,
+    journal::manager::JournalManager,
+    slow_op::SlowOp,
+    write_buffer_manager::WriteBufferManager,
+    PartitionHandle,
 };
 use lsm_tree::Segment;
 use std::{
@@ -13,7 +19,14 @@ use std::{
 fn run_flush_worker(task: &Arc<Task>) -> crate::Result<Arc<Segment>> {
     use lsm_tree::flush::Options;
 
-    let segment = lsm_tree::flush::flush_to_segment(Options {
+    task.partition.events.emit(|| Event::FlushStarted {
+        partition: task.partition.name.clone(),
+        memtable_size: task.sealed_memtable.size(),
+    });
+
+    let start = std::time::Instant::now();
+
+    let result = lsm_tree::flush::flush_to_segment(Options {
         tree_id: task.partition.tree.id,
 
         // IMPORTANT: Segment has to get the task ID
@@ -25,7 +38,27 @@ fn run_flush_worker(task: &Arc<Task>) -> crate::Result<Arc<Segment>> {
         block_size: task.partition.tree.config.inner.block_size,
         block_cache: task.partition.tree.config.block_cache.clone(),
         descriptor_table: task.partition.tree.config.descriptor_table.clone(),
-    })?;
+    });
+
+    // NOTE: Emit the finished event on error as well, so subscribers can track in-flight flushes
+    task.partition.events.emit(|| Event::FlushFinished {
+        partition: task.partition.name.clone(),
+        memtable_size: task.sealed_memtable.size(),
+        duration: start.elapsed(),
+        error: result.as_ref().err().map(|e| format!("{e:?}")),
+    });
+
+    let segment = result?;
+
+    if let Some(observer) = &task.partition.keyspace_config.slow_op_observer {
+        observer.observe(start.elapsed(), |duration| SlowOp::Flush {
+            partition: task.partition.name.clone(),
+            memtable_size: task.sealed_memtable.size(),
+            duration,
+        });
+    }
+
+    fail_point!(task.partition.keyspace_config, "flush::before_register");
 
     Ok(Arc::new(segment))
 }
@@ -40,10 +73,68 @@ struct MultiFlushResultItem {
 
 type MultiFlushResults = Vec<crate::Result<MultiFlushResultItem>>;
 
+/// Flushes all tasks of a single partition.
+///
+/// Each task is flushed in its own thread, unless `is_inline` is set.
+fn flush_partition(tasks: Vec<Arc<Task>>, is_inline: bool) -> crate::Result<MultiFlushResultItem> {
+    let partition = tasks
+        .first()
+        .expect("should always have at least one task")
+        .partition
+        .clone();
+
+    let memtables_size: u64 = tasks
+        .iter()
+        .map(|t| u64::from(t.sealed_memtable.size()))
+        .sum();
+
+    let created_segments = if is_inline {
+        tasks
+            .iter()
+            .map(run_flush_worker)
+            .collect::<crate::Result<Vec<_>>>()?
+    } else {
+        // NOTE: Don't trust clippy
+        #[allow(clippy::needless_collect)]
+        let flush_workers = tasks
+            .into_iter()
+            .map(|task| {
+                std::thread::Builder::new()
+                    .name("fjall-fseg".into())
+                    .spawn(move || run_flush_worker(&task))
+                    .expect("should spawn flush thread")
+            })
+            .collect::<Vec<_>>();
+
+        flush_workers
+            .into_iter()
+            .map(|t| t.join().expect("should join"))
+            .collect::<crate::Result<Vec<_>>>()?
+    };
+
+    Ok(MultiFlushResultItem {
+        partition,
+        created_segments,
+        size: memtables_size,
+    })
+}
+
 /// Distributes tasks of multiple partitions over multiple worker threads.
 ///
 /// Each thread is responsible for the tasks of one partition.
-fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) -> MultiFlushResults {
+///
+/// If `is_inline` is set, all tasks are flushed in the calling thread instead.
+fn run_multi_flush(
+    partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>,
+    is_inline: bool,
+) -> MultiFlushResults {
+    if is_inline {
+        return partitioned_tasks
+            .values()
+            .map(|tasks| flush_partition(tasks.clone(), true))
+            .collect();
+    }
+
     log::debug!(
         "flush worker: spawning {} worker threads",
         partitioned_tasks.len()
@@ -57,41 +148,17 @@ fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) ->
             let partition_name = partition_name.clone();
             let tasks = tasks.clone();
 
-            std::thread::spawn(move || {
-                log::trace!(
-                    "flush thread: flushing {} memtables for partition {partition_name:?}",
-                    tasks.len()
-                );
-
-                let partition = tasks
-                    .first()
-                    .expect("should always have at least one task")
-                    .partition
-                    .clone();
-
-                let memtables_size: u64 = tasks
-                    .iter()
-                    .map(|t| u64::from(t.sealed_memtable.size()))
-                    .sum();
-
-                // NOTE: Don't trust clippy
-                #[allow(clippy::needless_collect)]
-                let flush_workers = tasks
-                    .into_iter()
-                    .map(|task| std::thread::spawn(move || run_flush_worker(&task)))
-                    .collect::<Vec<_>>();
-
-                let created_segments = flush_workers
-                    .into_iter()
-                    .map(|t| t.join().expect("should join"))
-                    .collect::<crate::Result<Vec<_>>>()?;
-
-                Ok(MultiFlushResultItem {
-                    partition,
-                    created_segments,
-                    size: memtables_size,
+            std::thread::Builder::new()
+                .name("fjall-fpart".into())
+                .spawn(move || {
+                    log::trace!(
+                        "flush thread: flushing {} memtables for partition {partition_name:?}",
+                        tasks.len()
+                    );
+
+                    flush_partition(tasks, false)
                 })
-            })
+                .expect("should spawn flush thread")
         })
         .collect::<Vec<_>>();
 
@@ -102,6 +169,8 @@ fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) ->
 }
 
 /// Runs flush logic.
+///
+/// If `is_inline` is set, no threads are spawned.
 #[allow(clippy::too_many_lines)]
 pub fn run(
     flush_manager: &Arc<RwLock<FlushManager>>,
@@ -109,6 +178,7 @@ pub fn run(
     compaction_manager: &CompactionManager,
     write_buffer_manager: &WriteBufferManager,
     parallelism: usize,
+    is_inline: bool,
 ) {
     log::debug!("flush worker: write locking flush manager");
     let mut fm = flush_manager.write().expect("lock is poisoned");
@@ -122,7 +192,7 @@ pub fn run(
         return;
     }
 
-    for result in run_multi_flush(&partitioned_tasks) {
+    for result in run_multi_flush(&partitioned_tasks, is_inline) {
         match result {
             Ok(MultiFlushResultItem {
                 partition,
@@ -139,7 +209,7 @@ pub fn run(
                     flush_manager.dequeue_tasks(partition.name.clone(), created_segments.len());
 
                     write_buffer_manager.free(memtables_size);
-                    compaction_manager.notify(partition);
+                    compaction_manager.notify(partition, CompactionReason::Flush);
                 }
             }
             Err(e) => {
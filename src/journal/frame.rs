@@ -0,0 +1,271 @@
+use super::{
+    marker::Marker,
+    reader::JournalShardReader,
+    shard::{corrupt_journal, RecoveryError},
+};
+use crate::batch::item::Item as BatchItem;
+use lsm_tree::{
+    serde::{Deserializable, Serializable},
+    SeqNo,
+};
+use std::{io::Cursor, path::Path};
+
+/// A single batch, serialized exactly like it is stored in the journal
+///
+/// The frame consists of the start marker, the batch's items, and the end marker
+/// which contains the CRC of the items, so frames can be validated on their own.
+pub type Frame = (SeqNo, Vec<u8>);
+
+/// Reads all complete and valid batches with a seqno >= `from_seqno` from a journal shard file
+///
+/// Never modifies the file, so it is safe to use on the active journal.
+pub fn read_shard_frames<P: AsRef<Path>>(
+    path: P,
+    from_seqno: SeqNo,
+    frames: &mut Vec<Frame>,
+) -> crate::Result<()> {
+    let reader = JournalShardReader::new(path)?.with_repair(false);
+
+    let mut hasher = crc32fast::Hasher::new();
+    let mut is_in_batch = false;
+    let mut batch_counter = 0;
+    let mut batch_seqno = SeqNo::default();
+    let mut frame = vec![];
+
+    for item in reader {
+        let (_, marker) = item?;
+
+        match marker {
+            Marker::Start { item_count, seqno } => {
+                // NOTE: An unterminated batch is just dropped,
+                // as it can never become valid again
+                hasher = crc32fast::Hasher::new();
+                is_in_batch = true;
+                batch_counter = item_count;
+                batch_seqno = seqno;
+
+                frame.clear();
+                marker.serialize(&mut frame)?;
+            }
+            Marker::Item { .. } => {
+                if !is_in_batch {
+                    continue;
+                }
+
+                if batch_counter == 0 {
+                    is_in_batch = false;
+                    continue;
+                }
+
+                batch_counter -= 1;
+
+                let mut bytes = vec![];
+                marker.serialize(&mut bytes)?;
+                hasher.update(&bytes);
+                frame.extend_from_slice(&bytes);
+            }
+            Marker::End(checksum) => {
+                if !is_in_batch {
+                    continue;
+                }
+
+                is_in_batch = false;
+
+                let crc = std::mem::replace(&mut hasher, crc32fast::Hasher::new()).finalize();
+
+                if batch_counter > 0 || crc != checksum {
+                    log::debug!("Skipping invalid batch with seqno {batch_seqno}");
+                    continue;
+                }
+
+                if batch_seqno >= from_seqno {
+                    marker.serialize(&mut frame)?;
+                    frames.push((batch_seqno, std::mem::take(&mut frame)));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses and validates a batch frame
+///
+/// # Errors
+///
+/// Will return `Err` if the frame is malformed, or its checksum does not match.
+pub fn decode_frame(bytes: &[u8]) -> crate::Result<(SeqNo, Vec<BatchItem>)> {
+    use crate::Error::JournalRecovery;
+
+    let mut reader = Cursor::new(bytes);
+
+    let Marker::Start { item_count, seqno } = Marker::deserialize(&mut reader)? else {
+        return Err(JournalRecovery(RecoveryError::InsufficientLength));
+    };
+
+    let mut hasher = crc32fast::Hasher::new();
+    let mut items = vec![];
+
+    for _ in 0..item_count {
+        let marker = Marker::deserialize(&mut reader)?;
+
+        let mut bytes = vec![];
+        marker.serialize(&mut bytes)?;
+        hasher.update(&bytes);
+
+        let Marker::Item {
+            partition,
+            key,
+            value,
+            value_type,
+        } = marker
+        else {
+            return Err(JournalRecovery(RecoveryError::InsufficientLength));
+        };
+
+        // NOTE: Don't trust the frame, the write path never produces empty keys or partition names
+        if partition.is_empty() || key.is_empty() {
+            return Err(corrupt_journal(
+                "journal frame contains an empty key or partition name",
+            ));
+        }
+
+        items.push(BatchItem {
+            partition,
+            key,
+            value,
+            value_type,
+        });
+    }
+
+    let Marker::End(checksum) = Marker::deserialize(&mut reader)? else {
+        return Err(JournalRecovery(RecoveryError::TooManyItems));
+    };
+
+    if hasher.finalize() != checksum {
+        return Err(JournalRecovery(RecoveryError::CrcCheck));
+    }
+
+    if reader.position() != bytes.len() as u64 {
+        return Err(JournalRecovery(RecoveryError::TooManyItems));
+    }
+
+    Ok((seqno, items))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::journal::shard::JournalShard;
+    use lsm_tree::ValueType;
+    use tempfile::tempdir;
+    use test_log::test;
+
+    #[test]
+    fn journal_frame_roundtrip() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let shard_path = dir.path().join("0");
+
+        {
+            let mut shard = JournalShard::create_new(&shard_path)?;
+            shard.writer.write_batch(
+                &[&BatchItem::new("default", *b"a", *b"1", ValueType::Value)],
+                0,
+            )?;
+            shard.writer.write_batch(
+                &[
+                    &BatchItem::new("default", *b"b", *b"2", ValueType::Value),
+                    &BatchItem::new("default", *b"a", *b"", ValueType::Tombstone),
+                ],
+                1,
+            )?;
+            shard.writer.flush(crate::PersistMode::SyncAll)?;
+        }
+
+        let mut frames = vec![];
+        read_shard_frames(&shard_path, 1, &mut frames)?;
+        assert_eq!(1, frames.len());
+
+        let (seqno, bytes) = frames.first().expect("should exist");
+        assert_eq!(1, *seqno);
+
+        let (seqno, items) = decode_frame(bytes)?;
+        assert_eq!(1, seqno);
+        assert_eq!(2, items.len());
+
+        let mut frames = vec![];
+        read_shard_frames(&shard_path, 0, &mut frames)?;
+        assert_eq!(2, frames.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_frame_corrupt() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let shard_path = dir.path().join("0");
+
+        {
+            let mut shard = JournalShard::create_new(&shard_path)?;
+            shard.writer.write_batch(
+                &[&BatchItem::new(
+                    "default",
+                    *b"abc",
+                    *b"def",
+                    ValueType::Value,
+                )],
+                0,
+            )?;
+            shard.writer.flush(crate::PersistMode::SyncAll)?;
+        }
+
+        let mut frames = vec![];
+        read_shard_frames(&shard_path, 0, &mut frames)?;
+        let (_, mut bytes) = frames.pop().expect("should exist");
+
+        // Mangle the value
+        let idx = bytes
+            .windows(3)
+            .position(|x| x == b"def")
+            .expect("should exist");
+        bytes[idx] = b'x';
+
+        assert!(matches!(
+            decode_frame(&bytes),
+            Err(crate::Error::JournalRecovery(RecoveryError::CrcCheck))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_frame_empty_key() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let shard_path = dir.path().join("0");
+
+        {
+            let item = BatchItem {
+                partition: "default".into(),
+                key: (*b"").into(),
+                value: (*b"abc").into(),
+                value_type: ValueType::Value,
+            };
+
+            let mut shard = JournalShard::create_new(&shard_path)?;
+            shard.writer.write_batch(&[&item], 0)?;
+            shard.writer.flush(crate::PersistMode::SyncAll)?;
+        }
+
+        let mut frames = vec![];
+        read_shard_frames(&shard_path, 0, &mut frames)?;
+        let (_, bytes) = frames.pop().expect("should exist");
+
+        assert!(matches!(
+            decode_frame(&bytes),
+            Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
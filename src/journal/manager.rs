@@ -6,7 +6,13 @@ use crate::{
     PartitionHandle,
 };
 use lsm_tree::SeqNo;
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::RwLockWriteGuard};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::RwLockWriteGuard,
+};
 
 pub struct PartitionSeqNo {
     pub(crate) partition: PartitionHandle,
@@ -35,6 +41,32 @@ impl std::fmt::Debug for Item {
     }
 }
 
+/// Marks a journal as sealed by writing its partition list, followed by the flush marker
+///
+/// Each entry is the name of a partition that has data in the journal,
+/// and the highest seqno of that data.
+pub fn seal_journal<'a, P: AsRef<Path>>(
+    path: P,
+    partitions: impl IntoIterator<Item = (&'a str, SeqNo)>,
+) -> crate::Result<()> {
+    let path = path.as_ref();
+
+    let mut file = File::create(path.join(FLUSH_PARTITIONS_LIST))?;
+
+    for (name, lsn) in partitions {
+        writeln!(file, "{name}:{lsn}")?;
+    }
+    file.sync_all()?;
+
+    let marker = File::create(path.join(FLUSH_MARKER))?;
+    marker.sync_all()?;
+
+    // IMPORTANT: fsync folder on Unix
+    fsync_directory(path)?;
+
+    Ok(())
+}
+
 // TODO: accessing journal manager shouldn't take RwLock... but changing its internals should
 
 /// The [`JournalManager`] keeps track of sealed journals that are being flushed.
@@ -167,18 +199,10 @@ impl JournalManager {
 
         log::debug!("Sealing journal at {old_journal_path:?}");
 
-        let mut file = File::create(old_journal_path.join(FLUSH_PARTITIONS_LIST))?;
-
-        for (name, item) in &seqnos {
-            writeln!(file, "{name}:{}", item.lsn)?;
-        }
-        file.sync_all()?;
-
-        let marker = File::create(old_journal_path.join(FLUSH_MARKER))?;
-        marker.sync_all()?;
-
-        // IMPORTANT: fsync folder on Unix
-        fsync_directory(&old_journal_path)?;
+        seal_journal(
+            &old_journal_path,
+            seqnos.iter().map(|(name, item)| (&**name, item.lsn)),
+        )?;
 
         let old_journal_id = old_journal_path
             .file_name()
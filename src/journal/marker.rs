@@ -84,12 +84,12 @@ impl Serializable for Marker {
                 writer.write_u8(partition.as_bytes().len() as u8)?;
                 writer.write_all(partition.as_bytes())?;
 
-                // NOTE: Truncation is okay and actually needed
+                // NOTE: Cannot truncate, key size is checked on the write path
                 #[allow(clippy::cast_possible_truncation)]
                 writer.write_u16::<BigEndian>(key.len() as u16)?;
                 writer.write_all(key)?;
 
-                // NOTE: Truncation is okay and actually needed
+                // NOTE: Cannot truncate, value size is checked on the write path
                 #[allow(clippy::cast_possible_truncation)]
                 writer.write_u16::<BigEndian>(value.len() as u16)?;
                 writer.write_all(value)?;
@@ -1,3 +1,4 @@
+pub mod frame;
 pub mod manager;
 mod marker;
 pub mod partition_manifest;
@@ -10,7 +11,7 @@ use self::{
     writer::PersistMode,
 };
 use crate::{batch::PartitionKey, file::fsync_directory, sharded::Sharded};
-use lsm_tree::MemTable;
+use lsm_tree::{MemTable, SeqNo};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
@@ -47,13 +48,32 @@ impl Drop for Journal {
 }
 
 impl Journal {
+    /// Recovers the memtables of a journal, and the highest seqno of each partition
     pub fn recover_memtables<P: AsRef<Path>>(
         path: P,
         whitelist: Option<&[PartitionKey]>,
         recovery_mode: RecoveryMode,
-    ) -> crate::Result<HashMap<PartitionKey, MemTable>> {
-        let path = path.as_ref();
+    ) -> crate::Result<(
+        HashMap<PartitionKey, MemTable>,
+        HashMap<PartitionKey, SeqNo>,
+    )> {
         let mut memtables = HashMap::new();
+        let mut lsns = HashMap::new();
+
+        Self::recover_memtables_into(path, whitelist, recovery_mode, &mut memtables, &mut lsns)?;
+
+        Ok((memtables, lsns))
+    }
+
+    /// Recovers the memtables of a journal into existing memtables, e.g. to merge multiple journals
+    pub fn recover_memtables_into<P: AsRef<Path>>(
+        path: P,
+        whitelist: Option<&[PartitionKey]>,
+        recovery_mode: RecoveryMode,
+        memtables: &mut HashMap<PartitionKey, MemTable>,
+        lsns: &mut HashMap<PartitionKey, SeqNo>,
+    ) -> crate::Result<()> {
+        let path = path.as_ref();
 
         for idx in 0..SHARD_COUNT {
             let shard_path = get_shard_path(path, idx);
@@ -61,7 +81,8 @@ impl Journal {
             if shard_path.exists() {
                 JournalShard::recover_and_repair(
                     shard_path,
-                    &mut memtables,
+                    memtables,
+                    lsns,
                     whitelist,
                     recovery_mode,
                 )?;
@@ -71,17 +92,22 @@ impl Journal {
             }
         }
 
-        Ok(memtables)
+        Ok(())
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn recover<P: AsRef<Path>>(
         path: P,
         recovery_mode: RecoveryMode,
-    ) -> crate::Result<(Self, HashMap<PartitionKey, MemTable>)> {
+    ) -> crate::Result<(
+        Self,
+        HashMap<PartitionKey, MemTable>,
+        HashMap<PartitionKey, SeqNo>,
+    )> {
         let path = path.as_ref();
         log::debug!("Recovering journal from {path:?}");
 
-        let memtables = Self::recover_memtables(path, None, recovery_mode)?;
+        let (memtables, lsns) = Self::recover_memtables(path, None, recovery_mode)?;
 
         let shards = (0..SHARD_COUNT)
             .map(|idx| {
@@ -100,6 +126,7 @@ impl Journal {
                 path: path.to_path_buf(),
             },
             memtables,
+            lsns,
         ))
     }
 
@@ -160,6 +187,25 @@ impl Journal {
         shard
     }
 
+    /// Reads all valid batches with a seqno >= `from_seqno` from the journal at the given path
+    pub fn read_frames<P: AsRef<Path>>(
+        path: P,
+        from_seqno: SeqNo,
+    ) -> crate::Result<Vec<frame::Frame>> {
+        let path = path.as_ref();
+        let mut frames = vec![];
+
+        for idx in 0..SHARD_COUNT {
+            let shard_path = get_shard_path(path, idx);
+
+            if shard_path.try_exists()? {
+                frame::read_shard_frames(shard_path, from_seqno, &mut frames)?;
+            }
+        }
+
+        Ok(frames)
+    }
+
     /// Flushes the journal.
     pub fn flush(&self, mode: PersistMode) -> crate::Result<()> {
         for mut shard in self.full_lock() {
@@ -176,6 +222,7 @@ impl Journal {
 #[allow(clippy::expect_used)]
 mod tests {
     use super::marker::Marker;
+    use super::shard::RecoveryError;
     use super::*;
     use crate::batch::item::Item as BatchItem;
     use lsm_tree::{serde::Serializable, ValueType};
@@ -183,6 +230,41 @@ mod tests {
     use tempfile::tempdir;
     use test_log::test;
 
+    #[test]
+    fn test_log_recovery_lsns() -> crate::Result<()> {
+        let dir = tempdir()?;
+
+        {
+            let mut shard = JournalShard::create_new(dir.path().join("0"))?;
+            shard.writer.write_batch(
+                &[
+                    &BatchItem::new("a", *b"abc", *b"def", ValueType::Value),
+                    &BatchItem::new("b", *b"abc", *b"def", ValueType::Value),
+                ],
+                5,
+            )?;
+            shard.writer.write_batch(
+                &[&BatchItem::new("a", *b"yxc", *b"ghj", ValueType::Value)],
+                7,
+            )?;
+        }
+
+        {
+            let mut shard = JournalShard::create_new(dir.path().join("1"))?;
+            shard.writer.write_batch(
+                &[&BatchItem::new("b", *b"yxc", *b"ghj", ValueType::Value)],
+                6,
+            )?;
+        }
+
+        let (_, memtables, lsns) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+        assert_eq!(2, memtables.len());
+        assert_eq!(Some(&7), lsns.get("a"));
+        assert_eq!(Some(&6), lsns.get("b"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_log_truncation_corrupt_bytes() -> crate::Result<()> {
         let dir = tempdir()?;
@@ -199,7 +281,7 @@ mod tests {
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
             assert_eq!(memtable.len(), values.len());
         }
@@ -212,7 +294,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -227,7 +309,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -253,7 +335,7 @@ mod tests {
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             assert_eq!(memtable.len(), values.len());
@@ -271,7 +353,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -290,7 +372,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -316,7 +398,7 @@ mod tests {
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             assert_eq!(memtable.len(), values.len());
@@ -330,7 +412,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -345,7 +427,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -371,7 +453,7 @@ mod tests {
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             assert_eq!(memtable.len(), values.len());
@@ -392,7 +474,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -414,7 +496,7 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
             let memtable = memtables.get("default").expect("should exist");
 
             // Should recover all items
@@ -423,4 +505,170 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_log_recovery_absolute_consistency() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let shard_path = dir.path().join("0");
+
+        let values = [
+            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+        ];
+
+        {
+            let mut shard = JournalShard::create_new(&shard_path)?;
+            shard.writer.write_batch(&values, 0)?;
+        }
+
+        // NOTE: Pre-allocated (zeroed) tail is not considered corrupt
+        {
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::AbsoluteConsistency)?;
+            let memtable = memtables.get("default").expect("should exist");
+            assert_eq!(memtable.len(), values.len());
+        }
+
+        // Mangle journal
+        {
+            let mut file = std::fs::OpenOptions::new().append(true).open(&shard_path)?;
+            Marker::Start {
+                item_count: 2,
+                seqno: 64,
+            }
+            .serialize(&mut file)?;
+            file.sync_all()?;
+        }
+
+        assert!(matches!(
+            Journal::recover(&dir, RecoveryMode::AbsoluteConsistency),
+            Err(crate::Error::JournalRecovery(
+                RecoveryError::InsufficientLength
+            ))
+        ));
+
+        {
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let memtable = memtables.get("default").expect("should exist");
+            assert_eq!(memtable.len(), values.len());
+        }
+
+        // NOTE: Tail was discarded, so the journal is consistent again
+        {
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::AbsoluteConsistency)?;
+            let memtable = memtables.get("default").expect("should exist");
+            assert_eq!(memtable.len(), values.len());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_recovery_absolute_consistency_corrupt_tail() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let shard_path = dir.path().join("0");
+
+        let values = [
+            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+        ];
+
+        {
+            let mut shard = JournalShard::create_new(&shard_path)?;
+            shard.writer.write_batch(&values, 0)?;
+        }
+
+        // NOTE: Discard pre-allocated tail
+        Journal::recover(&dir, RecoveryMode::AbsoluteConsistency)?;
+
+        // Mangle journal with an invalid marker tag after the end marker
+        {
+            let mut file = std::fs::OpenOptions::new().append(true).open(&shard_path)?;
+            file.write_all(&[255, 1, 2, 3])?;
+            file.sync_all()?;
+        }
+
+        for _ in 0..3 {
+            assert!(matches!(
+                Journal::recover(&dir, RecoveryMode::AbsoluteConsistency),
+                Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData
+            ));
+        }
+
+        // NOTE: The garbage was not touched, so the default mode can still repair it
+        {
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+            let memtable = memtables.get("default").expect("should exist");
+            assert_eq!(memtable.len(), values.len());
+        }
+
+        {
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::AbsoluteConsistency)?;
+            let memtable = memtables.get("default").expect("should exist");
+            assert_eq!(memtable.len(), values.len());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_recovery_skip_invalid_batches() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let shard_path = dir.path().join("0");
+
+        let values = [
+            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+        ];
+
+        let values2 = [
+            &BatchItem::new("default", *b"qwe", *b"rty", ValueType::Value),
+            &BatchItem::new("default", *b"asd", *b"fgh", ValueType::Value),
+        ];
+
+        {
+            let mut shard = JournalShard::create_new(&shard_path)?;
+            shard.writer.write_batch(&values, 0)?;
+        }
+
+        // NOTE: Discard pre-allocated tail
+        Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
+
+        // Write batch with invalid CRC
+        {
+            let mut file = std::fs::OpenOptions::new().append(true).open(&shard_path)?;
+            Marker::Start {
+                item_count: 1,
+                seqno: 1,
+            }
+            .serialize(&mut file)?;
+            Marker::Item {
+                partition: "default".into(),
+                key: (*b"zzz").into(),
+                value: (*b"").into(),
+                value_type: ValueType::Tombstone,
+            }
+            .serialize(&mut file)?;
+            Marker::End(5432).serialize(&mut file)?;
+            file.sync_all()?;
+        }
+
+        {
+            let mut shard = JournalShard::from_file(&shard_path)?;
+            shard.writer.write_batch(&values2, 2)?;
+            shard.writer.flush(PersistMode::SyncAll)?;
+        }
+
+        assert!(matches!(
+            Journal::recover(&dir, RecoveryMode::TolerateCorruptTail),
+            Err(crate::Error::JournalRecovery(RecoveryError::CrcCheck))
+        ));
+
+        {
+            let (_, memtables, _) = Journal::recover(&dir, RecoveryMode::SkipInvalidBatches)?;
+            let memtable = memtables.get("default").expect("should exist");
+            assert_eq!(memtable.len(), values.len() + values2.len());
+        }
+
+        Ok(())
+    }
 }
@@ -15,6 +15,7 @@ use std::{
 pub struct JournalShardReader {
     reader: BufReader<File>,
     last_valid_pos: u64,
+    repair: bool,
 }
 
 impl JournalShardReader {
@@ -24,9 +25,19 @@ impl JournalShardReader {
         Ok(Self {
             reader: BufReader::new(file),
             last_valid_pos: 0,
+            repair: true,
         })
     }
 
+    /// Sets whether unparseable bytes at the end of the file should be truncated.
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn with_repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+
     fn truncate_file(&mut self, pos: u64) -> crate::Result<()> {
         log::debug!("truncating log to {pos}");
         self.reader.get_mut().set_len(pos)?;
@@ -35,6 +46,10 @@ impl JournalShardReader {
     }
 
     fn truncate_file_to_last_valid_pos(&mut self) -> crate::Result<()> {
+        if !self.repair {
+            return Ok(());
+        }
+
         self.truncate_file(self.last_valid_pos)
     }
 }
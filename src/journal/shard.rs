@@ -2,12 +2,18 @@ use super::{marker::Marker, writer::Writer as JournalWriter};
 use crate::batch::{item::Item as BatchItem, PartitionKey};
 use crate::journal::reader::JournalShardReader;
 use lsm_tree::{serde::Serializable, MemTable, SeqNo};
-use std::{collections::HashMap, fs::OpenOptions, path::Path};
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
 
 /// Recovery mode to use
 ///
 /// Based on `RocksDB`'s WAL Recovery Modes: <https://github.com/facebook/rocksdb/wiki/WAL-Recovery-Modes>
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum RecoveryMode {
     /// The last batch in the journal may be corrupt on crash,
     /// and will be discarded without error.
@@ -18,11 +24,20 @@ pub enum RecoveryMode {
     /// This is the default mode.
     #[default]
     TolerateCorruptTail,
-    // TODO: in the future?
-    /*  /// Skips corrupt (invalid CRC) batches. This may violate
+
+    /// Any corrupt or incomplete batch results in an error,
+    /// including an incomplete batch at the tail.
+    ///
+    /// Use if the journal is always persisted synchronously
+    /// (see [`crate::PersistMode`]), so an incomplete batch can only be caused
+    /// by data corruption.
+    AbsoluteConsistency,
+
+    /// Skips corrupt (invalid CRC) batches. This may violate
     /// consistency, but will recover as much data as possible.
-    SkipInvalidBatches, */
-    // TODO: absolute consistency
+    ///
+    /// Bytes that cannot be parsed at all still end the recovery of a journal file.
+    SkipInvalidBatches,
 }
 
 /// Errors that can occur during journal recovery
@@ -40,6 +55,13 @@ pub enum RecoveryError {
     CrcCheck,
 }
 
+/// Returns an error for journal data that cannot be parsed,
+/// reported as an I/O error of kind [`std::io::ErrorKind::InvalidData`].
+// TODO: dedicated RecoveryError variants in 2.0.0
+pub fn corrupt_journal(msg: &str) -> crate::Error {
+    crate::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, msg))
+}
+
 // TODO: don't require locking for sync check
 #[allow(clippy::module_name_repetitions)]
 pub struct JournalShard {
@@ -75,23 +97,78 @@ impl JournalShard {
         Ok(())
     }
 
+    /// Returns `true` if the file only contains zero bytes starting at the given position.
+    ///
+    /// Journal files are pre-allocated, so a clean tail consists of zeros.
+    fn is_zeroed_from(path: &Path, pos: u64) -> crate::Result<bool> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(pos))?;
+
+        let mut buf = [0; 4_096];
+
+        loop {
+            let read = file.read(&mut buf)?;
+
+            if read == 0 {
+                return Ok(true);
+            }
+
+            if buf.iter().take(read).any(|&b| b != 0) {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Discards everything after the last valid batch.
+    ///
+    /// In [`RecoveryMode::AbsoluteConsistency`], only the zeroed, pre-allocated
+    /// tail may be discarded, anything else returns the given error.
+    fn discard_tail(
+        path: &Path,
+        last_valid_pos: u64,
+        recovery_mode: RecoveryMode,
+        error: crate::Error,
+    ) -> crate::Result<()> {
+        if recovery_mode == RecoveryMode::AbsoluteConsistency
+            && !Self::is_zeroed_from(path, last_valid_pos)?
+        {
+            log::error!("Invalid batch: journal tail is corrupt or incomplete: {error:?}");
+            return Err(error);
+        }
+
+        Self::truncate_to(path, last_valid_pos)
+    }
+
     /// Recovers a journal shard and writes the items into the given memtable
     ///
+    /// The highest recovered seqno of each partition is taken from the batch
+    /// start markers and written into `lsns`, so the memtables don't need to be scanned.
+    ///
     /// Will truncate the file to the position of the last valid batch
     #[allow(clippy::too_many_lines)]
     pub fn recover_and_repair<P: AsRef<Path>>(
         path: P,
         memtables: &mut HashMap<PartitionKey, MemTable>,
+        lsns: &mut HashMap<PartitionKey, SeqNo>,
         whitelist: Option<&[PartitionKey]>,
-        _recovery_mode: RecoveryMode, // TODO:
+        recovery_mode: RecoveryMode,
     ) -> crate::Result<()> {
         use crate::Error::JournalRecovery;
 
         let path = path.as_ref();
-        let recoverer = JournalShardReader::new(path)?;
+
+        // NOTE: In absolute consistency mode, the tail needs to be checked
+        // before anything is truncated
+        let recoverer = JournalShardReader::new(path)?
+            .with_repair(recovery_mode != RecoveryMode::AbsoluteConsistency);
+
+        let skip_invalid = recovery_mode == RecoveryMode::SkipInvalidBatches;
 
         let mut hasher = crc32fast::Hasher::new();
         let mut is_in_batch = false;
+        let mut is_batch_invalid = false;
+        let mut needs_truncation = false;
+        let mut tail_error = None;
         let mut batch_counter = 0;
         let mut batch_seqno = SeqNo::default();
         let mut last_valid_pos = 0;
@@ -106,43 +183,72 @@ impl JournalShard {
                     if is_in_batch {
                         log::debug!("Invalid batch: found batch start inside batch");
 
-                        // Discard batch
-                        Self::truncate_to(path, last_valid_pos)?;
-
-                        break 'a;
+                        if skip_invalid {
+                            // Discard the unterminated batch, and continue with the new one
+                            items.clear();
+                            hasher = crc32fast::Hasher::new();
+                        } else {
+                            // Discard batch
+                            needs_truncation = true;
+                            tail_error = Some(JournalRecovery(RecoveryError::InsufficientLength));
+                            break 'a;
+                        }
                     }
 
                     is_in_batch = true;
+                    is_batch_invalid = false;
                     batch_counter = item_count;
                     batch_seqno = seqno;
                 }
                 Marker::End(checksum) => {
-                    if batch_counter > 0 {
-                        log::error!("Invalid batch: insufficient length");
-                        return Err(JournalRecovery(RecoveryError::InsufficientLength));
-                    }
-
                     if !is_in_batch {
-                        log::error!("Invalid batch: found end marker without start marker");
+                        log::debug!("Invalid batch: found end marker without start marker");
 
-                        // Discard batch
-                        Self::truncate_to(path, last_valid_pos)?;
+                        if skip_invalid {
+                            continue;
+                        }
 
+                        // Discard batch
+                        needs_truncation = true;
+                        tail_error = Some(corrupt_journal(
+                            "journal contains a marker outside of a batch",
+                        ));
                         break 'a;
                     }
 
                     let crc = hasher.finalize();
                     hasher = crc32fast::Hasher::new();
 
-                    if crc != checksum {
+                    let error = if batch_counter > 0 {
+                        Some(RecoveryError::InsufficientLength)
+                    } else if is_batch_invalid {
+                        Some(RecoveryError::TooManyItems)
+                    } else if crc != checksum {
                         log::error!("Invalid batch: checksum check failed, expected: {checksum}, got: {crc}");
-                        return Err(JournalRecovery(RecoveryError::CrcCheck));
-                    }
+                        Some(RecoveryError::CrcCheck)
+                    } else {
+                        None
+                    };
 
                     // Reset all variables
                     is_in_batch = false;
+                    is_batch_invalid = false;
                     batch_counter = 0;
 
+                    if let Some(error) = error {
+                        if skip_invalid {
+                            log::warn!(
+                                "Skipping invalid batch with seqno {batch_seqno}: {error:?}"
+                            );
+                            items.clear();
+                            last_valid_pos = journal_file_pos;
+                            continue;
+                        }
+
+                        log::error!("Invalid batch: {error:?}");
+                        return Err(JournalRecovery(error));
+                    }
+
                     // NOTE: Clippy says into_iter() is better
                     // but in this case probably not
                     #[allow(clippy::iter_with_drain)]
@@ -153,6 +259,9 @@ impl JournalShard {
                             }
                         }
 
+                        let lsn = lsns.entry(item.partition.clone()).or_default();
+                        *lsn = (*lsn).max(batch_seqno);
+
                         let memtable = memtables.entry(item.partition).or_default();
 
                         let value = lsm_tree::Value {
@@ -185,16 +294,28 @@ impl JournalShard {
                     hasher.update(&bytes);
 
                     if !is_in_batch {
-                        log::debug!("Invalid batch: found end marker without start marker");
+                        log::debug!("Invalid batch: found item without start marker");
 
-                        // Discard batch
-                        Self::truncate_to(path, last_valid_pos)?;
+                        if skip_invalid {
+                            continue;
+                        }
 
+                        // Discard batch
+                        needs_truncation = true;
+                        tail_error = Some(corrupt_journal(
+                            "journal contains a marker outside of a batch",
+                        ));
                         break 'a;
                     }
 
                     if batch_counter == 0 {
                         log::error!("Invalid batch: Expected end marker (too many items in batch)");
+
+                        if skip_invalid {
+                            is_batch_invalid = true;
+                            continue;
+                        }
+
                         return Err(JournalRecovery(RecoveryError::TooManyItems));
                     }
 
@@ -212,9 +333,16 @@ impl JournalShard {
 
         if is_in_batch {
             log::debug!("Invalid batch: missing terminator, but last batch, so probably incomplete, discarding to keep atomicity");
+            needs_truncation = true;
+            tail_error = Some(JournalRecovery(RecoveryError::InsufficientLength));
+        }
 
-            // Discard batch
-            Self::truncate_to(path, last_valid_pos)?;
+        // NOTE: In absolute consistency mode, the reader does not repair the file,
+        // and stops at unparseable bytes without an error, so the tail always needs
+        // to be checked, otherwise new writes would be appended after the garbage
+        if needs_truncation || recovery_mode == RecoveryMode::AbsoluteConsistency {
+            let error = tail_error.unwrap_or_else(|| corrupt_journal("journal tail is corrupt"));
+            Self::discard_tail(path, last_valid_pos, recovery_mode, error)?;
         }
 
         Ok(())
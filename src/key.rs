@@ -0,0 +1,238 @@
+//! Helpers to build order-preserving composite keys.
+//!
+//! Keys are compared lexicographically (byte-wise), so composite keys
+//! need to be encoded in a way that keeps the logical order intact:
+//!
+//! - Integers are encoded as big-endian, so `2 < 10` holds for the encoded bytes, too
+//! - Tuple elements are escaped and terminated, so `("a", "b") < ("ab",)`
+//! - Timestamps are appended as a big-endian suffix, so all versions of a prefix
+//!   are stored next to each other, ordered by time
+//!
+//! # Examples
+//!
+//! ```
+//! use fjall::key;
+//!
+//! let a = key::encode_tuple(["tenant-1", "a"]);
+//! let b = key::encode_tuple(["tenant-1", "b"]);
+//! assert!(a < b);
+//!
+//! let parts = key::decode_tuple(&a).expect("should be valid tuple");
+//! assert_eq!(parts, [b"tenant-1".to_vec(), b"a".to_vec()]);
+//!
+//! let k = key::with_timestamp("sensor-1#", 1_700_000_000);
+//! assert_eq!(Some((&b"sensor-1#"[..], 1_700_000_000)), key::split_timestamp(&k));
+//! ```
+
+use std::ops::{Bound, RangeBounds};
+
+const TUPLE_SEPARATOR: u8 = 0x00;
+const TUPLE_TERMINATOR: u8 = 0x01;
+const TUPLE_ESCAPE: u8 = 0xFF;
+
+/// Encodes an integer so that byte-wise order equals numeric order.
+#[must_use]
+pub fn encode_u64(n: u64) -> [u8; 8] {
+    n.to_be_bytes()
+}
+
+/// Decodes an integer that was encoded using [`encode_u64`].
+///
+/// Returns `None` if the slice is not exactly 8 bytes long.
+#[must_use]
+pub fn decode_u64(bytes: &[u8]) -> Option<u64> {
+    let bytes: [u8; 8] = bytes.try_into().ok()?;
+    Some(u64::from_be_bytes(bytes))
+}
+
+/// Encodes a tuple of byte strings into a single key.
+///
+/// Every element is terminated by `0x00 0x01`; `0x00` bytes inside an element
+/// are escaped as `0x00 0xFF`. This keeps the lexicographic order of the
+/// tuple intact, and allows prefix searches over the leading elements, because
+/// encoding only the leading elements results in a prefix of the full key.
+#[must_use]
+pub fn encode_tuple<I: IntoIterator<Item = T>, T: AsRef<[u8]>>(parts: I) -> Vec<u8> {
+    let mut key = vec![];
+
+    for part in parts {
+        push_tuple_element(&mut key, part.as_ref());
+    }
+
+    key
+}
+
+fn push_tuple_element(key: &mut Vec<u8>, part: &[u8]) {
+    for &byte in part {
+        key.push(byte);
+
+        if byte == TUPLE_SEPARATOR {
+            key.push(TUPLE_ESCAPE);
+        }
+    }
+
+    key.push(TUPLE_SEPARATOR);
+    key.push(TUPLE_TERMINATOR);
+}
+
+/// Decodes a key that was encoded using [`encode_tuple`].
+///
+/// Returns `None` if the key is not a valid tuple.
+#[must_use]
+pub fn decode_tuple(key: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut parts = vec![];
+    let mut current = vec![];
+    let mut iter = key.iter();
+    let mut is_terminated = true;
+
+    while let Some(&byte) = iter.next() {
+        if byte == TUPLE_SEPARATOR {
+            match iter.next() {
+                Some(&TUPLE_ESCAPE) => current.push(TUPLE_SEPARATOR),
+                Some(&TUPLE_TERMINATOR) => {
+                    parts.push(std::mem::take(&mut current));
+                    is_terminated = true;
+                    continue;
+                }
+                _ => return None,
+            }
+        } else {
+            current.push(byte);
+        }
+
+        is_terminated = false;
+    }
+
+    // NOTE: Every element needs to be terminated
+    is_terminated.then_some(parts)
+}
+
+/// Appends a big-endian timestamp to the given prefix.
+///
+/// All keys of the same prefix are then ordered by time, which
+/// allows scanning time ranges using [`crate::PartitionHandle::prefix_range`].
+#[must_use]
+pub fn with_timestamp<K: AsRef<[u8]>>(prefix: K, timestamp: u64) -> Vec<u8> {
+    let prefix = prefix.as_ref();
+
+    let mut key = Vec::with_capacity(prefix.len() + std::mem::size_of::<u64>());
+    key.extend_from_slice(prefix);
+    key.extend_from_slice(&encode_u64(timestamp));
+    key
+}
+
+/// Splits a key that was built using [`with_timestamp`] into its prefix and timestamp.
+///
+/// Returns `None` if the key is shorter than 8 bytes.
+#[must_use]
+pub fn split_timestamp(key: &[u8]) -> Option<(&[u8], u64)> {
+    let split_at = key.len().checked_sub(std::mem::size_of::<u64>())?;
+    let (prefix, timestamp) = key.split_at(split_at);
+    Some((prefix, decode_u64(timestamp)?))
+}
+
+/// Converts a time range into a key range over keys built using [`with_timestamp`].
+pub(crate) fn timestamp_range<R: RangeBounds<u64>>(
+    prefix: &[u8],
+    range: &R,
+) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    let start = match range.start_bound() {
+        Bound::Included(&ts) => Bound::Included(with_timestamp(prefix, ts)),
+        Bound::Excluded(&ts) => match ts.checked_add(1) {
+            Some(ts) => Bound::Included(with_timestamp(prefix, ts)),
+
+            // NOTE: Nothing can come after u64::MAX, so return an empty range
+            None => {
+                let key = with_timestamp(prefix, u64::MAX);
+                return (Bound::Included(key.clone()), Bound::Excluded(key));
+            }
+        },
+        Bound::Unbounded => Bound::Included(with_timestamp(prefix, u64::MIN)),
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&ts) => Bound::Included(with_timestamp(prefix, ts)),
+        Bound::Excluded(&ts) => Bound::Excluded(with_timestamp(prefix, ts)),
+        Bound::Unbounded => Bound::Included(with_timestamp(prefix, u64::MAX)),
+    };
+
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn key_u64_order() {
+        assert!(encode_u64(2) < encode_u64(10));
+        assert!(encode_u64(255) < encode_u64(256));
+        assert_eq!(Some(256), decode_u64(&encode_u64(256)));
+        assert_eq!(None, decode_u64(&[0, 1]));
+    }
+
+    #[test]
+    fn key_tuple_order() {
+        let a = encode_tuple(["a", "b"]);
+        let ab = encode_tuple(["ab"]);
+        let a_nul = encode_tuple([&b"a\0"[..]]);
+        let a_only = encode_tuple(["a"]);
+
+        assert!(a < ab);
+        assert!(a_only < a_nul);
+        assert!(a_only < a);
+        assert!(a.starts_with(&encode_tuple(["a"])));
+        assert!(!ab.starts_with(&encode_tuple(["a"])));
+    }
+
+    #[test]
+    fn key_tuple_round_trip() {
+        let parts: [&[u8]; 4] = [b"abc", b"", b"\0\0", b"\xFF\0\xFF"];
+        let key = encode_tuple(parts);
+
+        let decoded = decode_tuple(&key).expect("should be valid tuple");
+        assert_eq!(
+            parts.iter().map(|x| x.to_vec()).collect::<Vec<_>>(),
+            decoded
+        );
+    }
+
+    #[test]
+    fn key_tuple_empty_element() {
+        let parts: [&[u8]; 2] = [b"", b"\xFF"];
+        let key = encode_tuple(parts);
+
+        let decoded = decode_tuple(&key).expect("should be valid tuple");
+        assert_eq!(
+            parts.iter().map(|x| x.to_vec()).collect::<Vec<_>>(),
+            decoded
+        );
+    }
+
+    #[test]
+    fn key_tuple_invalid() {
+        assert_eq!(None, decode_tuple(b"abc"));
+        assert_eq!(None, decode_tuple(b"abc\0"));
+        assert_eq!(None, decode_tuple(b"abc\0\x02"));
+    }
+
+    #[test]
+    fn key_timestamp_round_trip() {
+        let key = with_timestamp("abc", 5);
+        assert_eq!(Some((&b"abc"[..], 5)), split_timestamp(&key));
+        assert_eq!(None, split_timestamp(b"abc"));
+        assert!(with_timestamp("abc", 5) < with_timestamp("abc", 6));
+    }
+
+    #[test]
+    fn key_timestamp_range() {
+        let (start, end) = timestamp_range(b"a", &(5..10));
+        assert_eq!(Bound::Included(with_timestamp("a", 5)), start);
+        assert_eq!(Bound::Excluded(with_timestamp("a", 10)), end);
+
+        let (start, end) = timestamp_range(b"a", &(Bound::Excluded(5), Bound::Unbounded));
+        assert_eq!(Bound::Included(with_timestamp("a", 6)), start);
+        assert_eq!(Bound::Included(with_timestamp("a", u64::MAX)), end);
+    }
+}
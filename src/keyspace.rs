@@ -1,29 +1,42 @@
 use crate::{
     batch::{Batch, PartitionKey},
+    commit_pipeline::CommitPipeline,
     compaction::manager::CompactionManager,
     config::Config,
+    event::{Event, EventBus},
     file::{
         fsync_directory, FJALL_MARKER, FLUSH_MARKER, JOURNALS_FOLDER, PARTITIONS_FOLDER,
         PARTITION_DELETED_MARKER,
     },
     flush::manager::FlushManager,
-    journal::{manager::JournalManager, shard::RecoveryMode, writer::PersistMode, Journal},
+    journal::{
+        manager::{seal_journal, JournalManager},
+        shard::RecoveryMode,
+        writer::PersistMode,
+        Journal,
+    },
     monitor::Monitor,
-    partition::name::is_valid_partition_name,
-    recovery::{recover_partitions, recover_sealed_memtables},
+    partition::{
+        name::is_valid_partition_name,
+        sharded::{load_shard_count, store_shard_count},
+    },
+    recovery::{recover_partitions, recover_sealed_memtables, remove_orphans},
+    replication::load_replicated_seqno,
     version::Version,
     write_buffer_manager::WriteBufferManager,
-    PartitionCreateOptions, PartitionHandle,
+    PartitionCreateOptions, PartitionHandle, ShardedPartition,
 };
-use lsm_tree::{MemTable, SequenceNumberCounter};
+use lsm_tree::{MemTable, SeqNo, SequenceNumberCounter};
 use std::{
     collections::HashMap,
     fs::{remove_dir_all, File},
     path::Path,
     sync::{
         atomic::{AtomicBool, AtomicUsize},
-        Arc, RwLock,
+        mpsc::Receiver,
+        Arc, Mutex, RwLock,
     },
+    thread::ThreadId,
 };
 use std_semaphore::Semaphore;
 
@@ -44,6 +57,9 @@ pub struct KeyspaceInner {
     /// Current sequence number
     pub(crate) seqno: SequenceNumberCounter,
 
+    /// Allocates seqnos for writes, and tracks which of them are visible
+    pub(crate) commit_pipeline: CommitPipeline,
+
     /// Caps write buffer size by flushing
     /// memtables to disk segments
     pub(crate) flush_manager: Arc<RwLock<FlushManager>>,
@@ -70,6 +86,27 @@ pub struct KeyspaceInner {
 
     /// True if fsync failed
     pub(crate) is_poisoned: Arc<AtomicBool>,
+
+    /// Seqno of the last batch that was applied from another keyspace
+    pub(crate) replicated_seqno: Mutex<Option<SeqNo>>,
+
+    /// Background threads that were spawned by the keyspace
+    pub(crate) background_workers: Mutex<Vec<BackgroundWorker>>,
+
+    /// Subscribers of background events
+    pub(crate) events: EventBus,
+}
+
+/// A background thread of a keyspace
+///
+/// See [`Keyspace::background_workers`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackgroundWorker {
+    /// Thread name
+    pub name: String,
+
+    /// Thread ID
+    pub id: ThreadId,
 }
 
 impl Drop for KeyspaceInner {
@@ -155,10 +192,17 @@ impl Keyspace {
     /// ```
     #[must_use]
     pub fn batch(&self) -> Batch {
-        // TODO: maybe allow setting a custom capacity
         Batch::with_capacity(self.clone(), 10)
     }
 
+    /// Initializes a new atomic write batch with room for at least `capacity` items.
+    ///
+    /// See [`Keyspace::batch`].
+    #[must_use]
+    pub fn batch_with_capacity(&self, capacity: usize) -> Batch {
+        Batch::with_capacity(self.clone(), capacity)
+    }
+
     /// Returns the current write buffer size (active + sealed memtables).
     #[must_use]
     pub fn write_buffer_size(&self) -> u64 {
@@ -266,7 +310,10 @@ impl Keyspace {
     /// Returns error, if an IO error occured.
     pub fn open(config: Config) -> crate::Result<Self> {
         let keyspace = Self::create_or_recover(config)?;
-        keyspace.start_background_threads();
+
+        if keyspace.config.background_threads {
+            keyspace.start_background_threads();
+        }
 
         #[cfg(feature = "__internal_integration")]
         crate::drop::increment_drop_counter();
@@ -313,8 +360,8 @@ impl Keyspace {
             self.config.compaction_workers_count
         );
 
-        for _ in 0..self.config.compaction_workers_count {
-            self.spawn_compaction_worker();
+        for idx in 0..self.config.compaction_workers_count {
+            self.spawn_compaction_worker(idx);
         }
 
         if let Some(ms) = self.config.fsync_ms {
@@ -394,6 +441,90 @@ impl Keyspace {
         })
     }
 
+    /// Creates or opens a sharded partition, which spreads its keys over `shard_count` partitions.
+    ///
+    /// The shards are named `<name>_<index>`, so those names should not be used for other partitions.
+    ///
+    /// The shard count is stored in every shard, and needs to stay the same when reopening
+    /// the partition, otherwise keys would be routed to the wrong shards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let partition =
+    ///     keyspace.open_sharded_partition("events", 4, PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// assert!(partition.contains_key("a")?);
+    /// assert_eq!(4, keyspace.partition_count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// Will return an [`crate::Error::Io`] of kind [`std::io::ErrorKind::InvalidInput`]
+    /// if the partition was created with a different shard count, or of kind
+    /// [`std::io::ErrorKind::AlreadyExists`] if a shard name is taken by a non-empty
+    /// partition that is not a shard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shard count is 0, or the shard names are not valid partition names.
+    pub fn open_sharded_partition(
+        &self,
+        name: &str,
+        shard_count: usize,
+        create_options: PartitionCreateOptions,
+    ) -> crate::Result<ShardedPartition> {
+        assert!(shard_count > 0, "shard count should be at least 1");
+
+        let mut shards = Vec::with_capacity(shard_count);
+
+        for idx in 0..shard_count {
+            let shard_name = format!("{name}_{idx}");
+            let existed = self.partition_exists(&shard_name);
+
+            let shard = self.open_partition(&shard_name, create_options.clone())?;
+            let path = shard.path();
+
+            // TODO: dedicated error variants in 2.0.0
+            match load_shard_count(&path)? {
+                Some(stored) if stored == shard_count as u64 => {}
+                Some(stored) => {
+                    return Err(crate::Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("partition {name:?} has {stored} shards, not {shard_count}"),
+                    )));
+                }
+                None => {
+                    // NOTE: An empty partition without marker is either new,
+                    // or we crashed before its marker was written
+                    if existed && !shard.is_empty()? {
+                        return Err(crate::Error::Io(std::io::Error::new(
+                            std::io::ErrorKind::AlreadyExists,
+                            format!(
+                                "partition {shard_name:?} exists, but is not a shard of {name:?}"
+                            ),
+                        )));
+                    }
+
+                    store_shard_count(&path, shard_count as u64)?;
+                }
+            }
+
+            shards.push(shard);
+        }
+
+        Ok(ShardedPartition::new(shards))
+    }
+
     /// Returns the amount of partitions
     #[must_use]
     pub fn partition_count(&self) -> usize {
@@ -474,7 +605,7 @@ impl Keyspace {
     /// ```
     #[must_use]
     pub fn instant(&self) -> crate::Instant {
-        self.seqno.get()
+        self.commit_pipeline.visible_seqno()
     }
 
     fn check_version<P: AsRef<Path>>(path: P) -> crate::Result<()> {
@@ -498,9 +629,13 @@ impl Keyspace {
         recovery_mode: RecoveryMode,
     ) -> crate::Result<(
         lsm_tree::SegmentId,
-        Option<(Journal, HashMap<PartitionKey, MemTable>)>,
+        Option<(
+            Journal,
+            HashMap<PartitionKey, MemTable>,
+            HashMap<PartitionKey, SeqNo>,
+        )>,
     )> {
-        let mut journal = None;
+        let mut unsealed_journals = vec![];
         let mut max_journal_id = 0;
 
         for dirent in std::fs::read_dir(path)? {
@@ -516,11 +651,37 @@ impl Keyspace {
             max_journal_id = max_journal_id.max(journal_id);
 
             if !dirent.path().join(FLUSH_MARKER).try_exists()? {
-                journal = Some(Journal::recover(dirent.path(), recovery_mode)?);
+                unsealed_journals.push((journal_id, dirent.path()));
             }
         }
 
-        Ok((max_journal_id, journal))
+        // NOTE: Journal IDs are monotonically increasing on rotation,
+        // so sorting by ID orders the journals by their seqnos as well
+        unsealed_journals.sort_by_key(|(id, _)| *id);
+
+        let Some((_, active_journal_path)) = unsealed_journals.pop() else {
+            return Ok((max_journal_id, None));
+        };
+
+        // IMPORTANT: If we crashed at the wrong moment during journal rotation,
+        // there may be more than one unsealed journal
+        //
+        // Only the newest one can be active, so the older ones are sealed here,
+        // and then recovered (and flushed) like any other sealed journal
+        for (_, journal_path) in unsealed_journals {
+            log::warn!("Found unsealed journal {journal_path:?} that is not the active journal, sealing it");
+
+            let (_, lsns) = Journal::recover_memtables(&journal_path, None, recovery_mode)?;
+
+            seal_journal(
+                &journal_path,
+                lsns.iter().map(|(name, lsn)| (&**name, *lsn)),
+            )?;
+        }
+
+        let journal = Journal::recover(active_journal_path, recovery_mode)?;
+
+        Ok((max_journal_id, Some(journal)))
     }
 
     /// Recovers existing keyspace from directory.
@@ -533,33 +694,40 @@ impl Keyspace {
         // Check version
         Self::check_version(&config.path)?;
 
+        remove_orphans(&config.path)?;
+
         // Get active journal if it exists
         let journals_folder = config.path.join(JOURNALS_FOLDER);
         let (max_journal_id, active_journal) =
             Self::find_active_journal(&journals_folder, recovery_mode)?;
 
-        let (journal, mut memtables) = if let Some((journal, memtables)) = active_journal {
-            log::debug!("Recovered active journal at {:?}", journal.path);
-            (journal, memtables)
-        } else {
-            let journal =
-                Journal::create_new(journals_folder.join((max_journal_id + 1).to_string()))?;
+        let (journal, mut memtables, lsns) =
+            if let Some((journal, memtables, lsns)) = active_journal {
+                log::debug!("Recovered active journal at {:?}", journal.path);
+                (journal, memtables, lsns)
+            } else {
+                let journal =
+                    Journal::create_new(journals_folder.join((max_journal_id + 1).to_string()))?;
 
-            let memtables = HashMap::default();
-            (journal, memtables)
-        };
+                (journal, HashMap::default(), HashMap::default())
+            };
 
         let journal = Arc::new(journal);
         let journal_path = journal.path.clone();
 
         let journal_manager = JournalManager::new(journal_path);
 
+        let replicated_seqno = load_replicated_seqno(&config.path)?;
+
+        let seqno = SequenceNumberCounter::default();
+
         // Construct (empty) keyspace, then fill back with partition data
         let inner = KeyspaceInner {
             config,
             journal,
             partitions: Arc::new(RwLock::new(Partitions::with_capacity(10))),
-            seqno: SequenceNumberCounter::default(),
+            seqno: seqno.clone(),
+            commit_pipeline: CommitPipeline::new(seqno),
             flush_manager: Arc::new(RwLock::new(FlushManager::new())),
             journal_manager: Arc::new(RwLock::new(journal_manager)),
             flush_semaphore: Arc::new(Semaphore::new(0)),
@@ -568,10 +736,21 @@ impl Keyspace {
             active_background_threads: Arc::default(),
             write_buffer_manager: WriteBufferManager::default(),
             is_poisoned: Arc::default(),
+            replicated_seqno: Mutex::new(replicated_seqno),
+            background_workers: Mutex::default(),
+            events: EventBus::default(),
         };
 
         let keyspace = Self(Arc::new(inner));
 
+        // NOTE: The journal replay already gives us the highest seqno of the active memtables,
+        // so there is no need to scan them
+        if let Some(lsn) = lsns.values().max() {
+            keyspace
+                .seqno
+                .fetch_max(lsn + 1, std::sync::atomic::Ordering::AcqRel);
+        }
+
         // Recover partitions
         recover_partitions(&keyspace, &mut memtables)?;
 
@@ -601,11 +780,14 @@ impl Keyspace {
         let journal = Journal::create_new(&active_journal_path)?;
         let journal = Arc::new(journal);
 
+        let seqno = SequenceNumberCounter::default();
+
         let inner = KeyspaceInner {
             config,
             journal,
             partitions: Arc::new(RwLock::new(Partitions::with_capacity(10))),
-            seqno: SequenceNumberCounter::default(),
+            seqno: seqno.clone(),
+            commit_pipeline: CommitPipeline::new(seqno),
             flush_manager: Arc::new(RwLock::new(FlushManager::new())),
             journal_manager: Arc::new(RwLock::new(JournalManager::new(active_journal_path))),
             flush_semaphore: Arc::new(Semaphore::new(0)),
@@ -614,6 +796,9 @@ impl Keyspace {
             active_background_threads: Arc::default(),
             write_buffer_manager: WriteBufferManager::default(),
             is_poisoned: Arc::default(),
+            replicated_seqno: Mutex::default(),
+            background_workers: Mutex::default(),
+            events: EventBus::default(),
         };
 
         // NOTE: Lastly, fsync .fjall marker, which contains the version
@@ -630,6 +815,86 @@ impl Keyspace {
         Ok(Self(Arc::new(inner)))
     }
 
+    /// Subscribes to background events, like flushes, compactions and write stalls.
+    ///
+    /// Events are buffered in an unbounded channel, so the receiver should be drained
+    /// regularly. Dropping the receiver cancels the subscription.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// use fjall::Event;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder).background_threads(false).open()?;
+    /// let items = keyspace.open_partition("items", PartitionCreateOptions::default())?;
+    ///
+    /// let events = keyspace.events();
+    ///
+    /// items.insert("a", "abc")?;
+    /// items.rotate_memtable()?;
+    /// keyspace.maintenance();
+    ///
+    /// assert!(events
+    ///     .try_iter()
+    ///     .any(|event| matches!(event, Event::FlushFinished { error: None, .. })));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn events(&self) -> Receiver<Event> {
+        self.events.subscribe()
+    }
+
+    /// Returns the background threads that were spawned by this keyspace.
+    ///
+    /// Threads are named after their role (e.g. `fjall-flush`, `fjall-cmp-0`),
+    /// so they can be told apart in profilers and `top -H`.
+    /// Names are kept to at most 15 bytes, because Linux truncates longer thread names.
+    /// Threads spawned temporarily by flush workers are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::Config;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder).compaction_workers(2).open()?;
+    ///
+    /// let workers = keyspace.background_workers();
+    /// assert!(workers.iter().any(|worker| worker.name == "fjall-cmp-1"));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn background_workers(&self) -> Vec<BackgroundWorker> {
+        self.background_workers
+            .lock()
+            .expect("lock is poisoned")
+            .clone()
+    }
+
+    fn spawn_background_worker<F: FnOnce() + Send + 'static>(&self, name: String, f: F) {
+        log::debug!("Spawning background thread {name:?}");
+
+        // NOTE: Linux truncates thread names to 15 bytes
+        debug_assert!(name.len() <= 15, "thread name {name:?} is too long");
+
+        let handle = std::thread::Builder::new()
+            .name(name.clone())
+            .spawn(f)
+            .expect("should spawn background thread");
+
+        self.background_workers
+            .lock()
+            .expect("lock is poisoned")
+            .push(BackgroundWorker {
+                name,
+                id: handle.thread().id(),
+            });
+    }
+
     fn spawn_monitor_thread(&self) {
         let monitor = Monitor::new(self);
         let stop_signal = self.stop_signal.clone();
@@ -637,7 +902,7 @@ impl Keyspace {
 
         thread_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        std::thread::spawn(move || {
+        self.spawn_background_worker("fjall-monitor".into(), move || {
             while !stop_signal.is_stopped() {
                 let idle = monitor.run();
 
@@ -659,7 +924,7 @@ impl Keyspace {
 
         thread_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        std::thread::spawn(move || {
+        self.spawn_background_worker("fjall-fsync".into(), move || {
             while !stop_signal.is_stopped() {
                 log::trace!("fsync thread: sleeping {ms}ms");
                 std::thread::sleep(std::time::Duration::from_millis(ms as u64));
@@ -680,14 +945,14 @@ impl Keyspace {
         });
     }
 
-    fn spawn_compaction_worker(&self) {
+    fn spawn_compaction_worker(&self, idx: usize) {
         let compaction_manager = self.compaction_manager.clone();
         let stop_signal = self.stop_signal.clone();
         let thread_counter = self.active_background_threads.clone();
 
         thread_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        std::thread::spawn(move || {
+        self.spawn_background_worker(format!("fjall-cmp-{idx}"), move || {
             while !stop_signal.is_stopped() {
                 log::trace!("compaction: waiting for work");
                 compaction_manager.wait_for();
@@ -713,9 +978,49 @@ impl Keyspace {
             &self.compaction_manager,
             &self.write_buffer_manager,
             parallelism,
+            false,
         );
     }
 
+    /// Runs one round of maintenance in the calling thread.
+    ///
+    /// Rotates memtables if the journal or write buffer size limits are getting reached,
+    /// then flushes all sealed memtables, and runs all queued compactions.
+    ///
+    /// Only needed if background threads are disabled, see [`Config::background_threads`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder).background_threads(false).open()?;
+    /// let items = keyspace.open_partition("items", PartitionCreateOptions::default())?;
+    ///
+    /// items.insert("a", "abc")?;
+    /// items.rotate_memtable()?;
+    ///
+    /// keyspace.maintenance();
+    /// assert_eq!(1, items.segment_count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    pub fn maintenance(&self) {
+        Monitor::new(self).run();
+
+        crate::flush::worker::run(
+            &self.flush_manager,
+            &self.journal_manager,
+            &self.compaction_manager,
+            &self.write_buffer_manager,
+            self.config.flush_workers_count.max(1),
+            true,
+        );
+
+        crate::compaction::worker::run_until_empty(&self.compaction_manager);
+    }
+
     fn spawn_flush_worker(&self) {
         let flush_manager = self.flush_manager.clone();
         let journal_manager = self.journal_manager.clone();
@@ -730,7 +1035,7 @@ impl Keyspace {
 
         thread_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        std::thread::spawn(move || {
+        self.spawn_background_worker("fjall-flush".into(), move || {
             while !stop_signal.is_stopped() {
                 log::trace!("flush worker: acquiring flush semaphore");
                 flush_semaphore.acquire();
@@ -741,6 +1046,7 @@ impl Keyspace {
                     &compaction_manager,
                     &write_buffer_manager,
                     parallelism,
+                    false,
                 );
             }
 
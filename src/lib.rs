@@ -17,7 +17,7 @@
 //! - a relational database
 //! - a wide-column database: it has no notion of columns
 //!
-//! Keys are limited to 65536 bytes, values are limited to 65536 bytes. As is normal with any kind of storage engine, larger keys and values have a bigger performance impact.
+//! Keys are limited to 65535 bytes, values are limited to 65535 bytes. As is normal with any kind of storage engine, larger keys and values have a bigger performance impact.
 //!
 //! For the underlying LSM-tree implementation, see: <https://crates.io/crates/lsm-tree>.
 //!
@@ -79,41 +79,80 @@
 #![allow(clippy::missing_const_for_fn)]
 #![warn(clippy::multiple_crate_versions)]
 
+/// Evaluates a failpoint, see [`failpoints`].
+///
+/// Compiles to nothing if the `failpoints` feature is disabled.
+macro_rules! fail_point {
+    ($config:expr, $name:literal) => {
+        #[cfg(feature = "failpoints")]
+        $config.failpoints.eval($name)?;
+    };
+}
+
 mod batch;
+mod commit_pipeline;
 
 /// Contains compaction strategies
 pub mod compaction;
 
 mod config;
+mod diff;
 
 #[cfg(feature = "__internal_integration")]
 #[doc(hidden)]
 pub mod drop;
 
 mod error;
+mod event;
+
+#[cfg(feature = "failpoints")]
+pub mod failpoints;
+
 mod file;
 mod flush;
 mod journal;
+
+pub mod key;
+
 mod keyspace;
 mod monitor;
 mod partition;
 mod path;
 mod recovery;
+mod replication;
 mod sharded;
+mod slow_op;
 
 #[cfg(feature = "single_writer_tx")]
 mod tx;
 
+pub mod typed;
+
 mod version;
 mod write_buffer_manager;
 
 pub use {
     batch::Batch,
     config::Config,
+    diff::{diff, Diff, DiffEntry},
     error::{Error, Result},
-    journal::{shard::RecoveryError, writer::PersistMode},
-    keyspace::Keyspace,
-    partition::{config::CreateOptions as PartitionCreateOptions, PartitionHandle},
+    event::{CompactionReason, Event, StallReason},
+    journal::{
+        shard::{RecoveryError, RecoveryMode},
+        writer::PersistMode,
+    },
+    keyspace::{BackgroundWorker, Keyspace},
+    partition::{
+        config::CreateOptions as PartitionCreateOptions,
+        interceptor::WriteInterceptor,
+        lock::RangeLock,
+        scope::{Scope, ScopeSnapshot},
+        sharded::ShardedPartition,
+        watch::{WatchEvent, WatchReceiver},
+        PartitionHandle,
+    },
+    replication::ReplicatedBatch,
+    slow_op::SlowOp,
 };
 
 #[cfg(feature = "single_writer_tx")]
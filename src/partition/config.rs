@@ -1,4 +1,5 @@
 /// Options to configure a partition
+#[derive(Clone)]
 pub struct CreateOptions {
     /// Block size of data and index blocks
     ///
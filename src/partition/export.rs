@@ -0,0 +1,212 @@
+use super::PartitionHandle;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use lsm_tree::DeserializeError;
+use std::io::{Read, Write};
+
+const EXPORT_MAGIC: &[u8] = &[b'F', b'J', b'L', b'L', b'E', b'X', b'P', b'1'];
+
+const TAG_ITEM: u8 = 1;
+const TAG_END: u8 = 2;
+
+fn invalid_data(msg: &str) -> crate::Error {
+    crate::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, msg))
+}
+
+impl PartitionHandle {
+    /// Writes all items of the partition to a stream, returning the amount of items written.
+    ///
+    /// The items are read from a snapshot, so concurrent writes are not included.
+    ///
+    /// # Stream format
+    ///
+    /// The stream starts with an 8-byte magic, followed by every item
+    /// (tag `1`, big-endian `u16` key length, key, `u16` value length, value,
+    /// and the `u32` CRC32 of the preceding item bytes),
+    /// and is terminated by tag `2`, the `u64` item count and the CRC32 of all item bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let source = keyspace.open_partition("source", PartitionCreateOptions::default())?;
+    /// source.insert("a", "abc")?;
+    /// source.insert("b", "def")?;
+    ///
+    /// let mut backup = vec![];
+    /// assert_eq!(2, source.export(&mut backup)?);
+    ///
+    /// let target = keyspace.open_partition("target", PartitionCreateOptions::default())?;
+    /// assert_eq!(2, target.import(&mut &backup[..])?);
+    /// assert_eq!(2, target.len()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn export<W: Write>(&self, writer: &mut W) -> crate::Result<u64> {
+        let instant = self.commit_pipeline.visible_seqno();
+
+        writer.write_all(EXPORT_MAGIC)?;
+
+        let mut hasher = crc32fast::Hasher::new();
+        let mut item_count = 0u64;
+        let mut buf = vec![];
+
+        for item in self.tree.create_iter(Some(instant), None) {
+            let (key, value) = item?;
+
+            // NOTE: Cannot truncate, key/value size is checked on the write path
+            #[allow(clippy::cast_possible_truncation)]
+            {
+                buf.clear();
+                buf.write_u8(TAG_ITEM)?;
+                buf.write_u16::<BigEndian>(key.len() as u16)?;
+                buf.write_all(&key)?;
+                buf.write_u16::<BigEndian>(value.len() as u16)?;
+                buf.write_all(&value)?;
+            }
+
+            hasher.update(&buf);
+            writer.write_all(&buf)?;
+            writer.write_u32::<BigEndian>(crc32fast::hash(&buf))?;
+            item_count += 1;
+        }
+
+        writer.write_u8(TAG_END)?;
+        writer.write_u64::<BigEndian>(item_count)?;
+        writer.write_u32::<BigEndian>(hasher.finalize())?;
+        writer.flush()?;
+
+        Ok(item_count)
+    }
+
+    /// Inserts all items of a stream created by [`PartitionHandle::export`],
+    /// returning the amount of items imported.
+    ///
+    /// Existing items with the same keys are overwritten.
+    ///
+    /// Every item is checked against its checksum before it is written, so corrupt
+    /// items are never imported. However, if the stream turns out to be truncated
+    /// or otherwise invalid, an error is returned, but the (valid) items read up
+    /// to that point are **not** rolled back, so it's best to import into a new partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the stream is corrupt.
+    pub fn import<R: Read>(&self, reader: &mut R) -> crate::Result<u64> {
+        let mut magic = [0u8; EXPORT_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+
+        if magic != EXPORT_MAGIC {
+            return Err(invalid_data("invalid export header"));
+        }
+
+        let mut hasher = crc32fast::Hasher::new();
+        let mut item_count = 0u64;
+
+        loop {
+            let tag = reader.read_u8()?;
+
+            match tag {
+                TAG_ITEM => {
+                    let key_len = reader.read_u16::<BigEndian>()?;
+                    let mut key = vec![0; key_len.into()];
+                    reader.read_exact(&mut key)?;
+
+                    let value_len = reader.read_u16::<BigEndian>()?;
+                    let mut value = vec![0; value_len.into()];
+                    reader.read_exact(&mut value)?;
+
+                    let expected_item_crc = reader.read_u32::<BigEndian>()?;
+
+                    let mut item_hasher = crc32fast::Hasher::new();
+
+                    for bytes in [
+                        &[TAG_ITEM][..],
+                        &key_len.to_be_bytes()[..],
+                        &key[..],
+                        &value_len.to_be_bytes()[..],
+                        &value[..],
+                    ] {
+                        item_hasher.update(bytes);
+                        hasher.update(bytes);
+                    }
+
+                    // IMPORTANT: Check the item before writing it
+                    if expected_item_crc != item_hasher.finalize() {
+                        return Err(invalid_data("export item checksum mismatch"));
+                    }
+
+                    self.insert(key, value)?;
+                    item_count += 1;
+                }
+                TAG_END => {
+                    let expected_count = reader.read_u64::<BigEndian>()?;
+                    let expected_crc = reader.read_u32::<BigEndian>()?;
+
+                    if expected_count != item_count {
+                        return Err(invalid_data("export item count mismatch"));
+                    }
+
+                    if expected_crc != hasher.finalize() {
+                        return Err(invalid_data("export checksum mismatch"));
+                    }
+
+                    return Ok(item_count);
+                }
+                _ => {
+                    return Err(crate::Error::Deserialize(DeserializeError::InvalidTag((
+                        "ExportTag",
+                        tag,
+                    ))));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, PartitionCreateOptions};
+    use test_log::test;
+
+    #[test]
+    fn partition_export_corrupt() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        let keyspace = Config::new(folder).open()?;
+        let source = keyspace.open_partition("source", PartitionCreateOptions::default())?;
+        source.insert("a", "abc")?;
+
+        let mut backup = vec![];
+        source.export(&mut backup)?;
+
+        // Flip a bit inside the value
+        let idx = backup
+            .windows(3)
+            .position(|x| x == b"abc")
+            .expect("should contain value");
+        if let Some(byte) = backup.get_mut(idx) {
+            *byte ^= 1;
+        }
+
+        let target = keyspace.open_partition("target", PartitionCreateOptions::default())?;
+        assert!(matches!(
+            target.import(&mut &backup[..]),
+            Err(crate::Error::Io(_))
+        ));
+
+        // NOTE: The corrupt item must never be written
+        assert!(target.is_empty()?);
+
+        let target = keyspace.open_partition("target2", PartitionCreateOptions::default())?;
+        assert!(target.import(&mut &b"not an export"[..]).is_err());
+
+        Ok(())
+    }
+}
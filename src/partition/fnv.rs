@@ -0,0 +1,27 @@
+/// 128-bit FNV-1a hasher
+///
+/// Not cryptographically secure, but wide enough that accidental collisions
+/// are practically impossible, even when comparing many ranges.
+///
+/// See <http://www.isthe.com/chongo/tech/comp/fnv/>
+pub struct Fnv128(u128);
+
+impl Fnv128 {
+    const OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u128::from(byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub fn finish(&self) -> u128 {
+        self.0
+    }
+}
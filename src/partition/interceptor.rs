@@ -0,0 +1,67 @@
+/// Inspects writes before they are appended to the journal
+///
+/// Can be used to enforce quotas (e.g. per tenant), or to validate items.
+///
+/// Set using [`crate::PartitionHandle::set_write_interceptor`].
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, PartitionCreateOptions};
+/// use fjall::WriteInterceptor;
+/// use std::sync::Arc;
+///
+/// struct MaxValueSize(usize);
+///
+/// impl WriteInterceptor for MaxValueSize {
+///     fn intercept(
+///         &self,
+///         _key: &[u8],
+///         value_size: usize,
+///         _is_tombstone: bool,
+///     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+///         if value_size > self.0 {
+///             return Err("value over quota".into());
+///         }
+///         Ok(())
+///     }
+/// }
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let keyspace = Config::new(folder).open()?;
+/// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+/// partition.set_write_interceptor(Some(Arc::new(MaxValueSize(3))));
+///
+/// partition.insert("a", "abc")?;
+/// assert!(matches!(
+///     partition.insert("b", "abcd"),
+///     Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::PermissionDenied,
+/// ));
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+pub trait WriteInterceptor {
+    /// Called before an item is written, both for single writes and write batches.
+    ///
+    /// For removals, `value_size` is 0 and `is_tombstone` is `true`.
+    ///
+    /// The interceptor is called before the journal is locked, so it may read from,
+    /// or write to, the keyspace. However, it is not called atomically with the write,
+    /// so another write may happen in between.
+    ///
+    /// For the same reason, the interceptor may see writes that never happen,
+    /// e.g. if [`crate::PartitionHandle::insert_if_absent`] finds that the key was
+    /// written concurrently, or if the write fails afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returning `Err` rejects the write (and the entire batch, if the item is part of one),
+    /// which is then returned to the caller as an [`crate::Error::Io`] of kind
+    /// [`std::io::ErrorKind::PermissionDenied`].
+    fn intercept(
+        &self,
+        key: &[u8],
+        value_size: usize,
+        is_tombstone: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
@@ -0,0 +1,150 @@
+use lsm_tree::UserKey;
+use std::{
+    ops::{Bound, RangeBounds},
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+type KeyRange = (Bound<UserKey>, Bound<UserKey>);
+
+fn to_owned_bound<K: AsRef<[u8]> + ?Sized>(bound: Bound<&K>) -> Bound<UserKey> {
+    match bound {
+        Bound::Included(key) => Bound::Included(key.as_ref().into()),
+        Bound::Excluded(key) => Bound::Excluded(key.as_ref().into()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Returns `true` if there may be a key that is both >= `start` and <= `end`
+///
+/// Conservative for exclusive bounds, e.g. ("a", "a\0") is considered non-empty.
+fn is_start_before_end(start: &Bound<UserKey>, end: &Bound<UserKey>) -> bool {
+    match (start, end) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => true,
+        (Bound::Included(start), Bound::Included(end)) => start <= end,
+        (Bound::Included(start) | Bound::Excluded(start), Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end)) => start < end,
+    }
+}
+
+fn overlaps(a: &KeyRange, b: &KeyRange) -> bool {
+    is_start_before_end(&a.0, &b.1) && is_start_before_end(&b.0, &a.1)
+}
+
+#[derive(Default)]
+struct LockTable {
+    id_counter: u64,
+    held: Vec<(u64, KeyRange)>,
+}
+
+#[derive(Default)]
+struct LockManagerInner {
+    table: Mutex<LockTable>,
+    released: Condvar,
+}
+
+/// Keeps track of exclusively locked key ranges of a partition
+#[derive(Clone, Default)]
+pub struct LockManager(Arc<LockManagerInner>);
+
+impl LockManager {
+    pub fn lock<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+        timeout: Duration,
+    ) -> crate::Result<RangeLock> {
+        let range: KeyRange = (
+            to_owned_bound(range.start_bound()),
+            to_owned_bound(range.end_bound()),
+        );
+
+        // NOTE: If the deadline is not representable (e.g. Duration::MAX), wait forever
+        let deadline = Instant::now().checked_add(timeout);
+
+        let mut table = self.0.table.lock().expect("lock is poisoned");
+
+        while table.held.iter().any(|(_, held)| overlaps(held, &range)) {
+            let Some(deadline) = deadline else {
+                table = self.0.released.wait(table).expect("lock is poisoned");
+                continue;
+            };
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                // TODO: dedicated error variant in 2.0.0
+                return Err(crate::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    "key range lock could not be acquired in time",
+                )));
+            }
+
+            table = self
+                .0
+                .released
+                .wait_timeout(table, deadline - now)
+                .expect("lock is poisoned")
+                .0;
+        }
+
+        let id = table.id_counter;
+        table.id_counter += 1;
+        table.held.push((id, range));
+
+        Ok(RangeLock {
+            manager: self.clone(),
+            id,
+        })
+    }
+}
+
+/// An exclusive lock on a key range of a partition
+///
+/// The lock is released when dropped.
+///
+/// See [`crate::PartitionHandle::lock_range`].
+#[must_use]
+pub struct RangeLock {
+    manager: LockManager,
+    id: u64,
+}
+
+impl std::fmt::Debug for RangeLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RangeLock({})", self.id)
+    }
+}
+
+impl Drop for RangeLock {
+    fn drop(&mut self) {
+        let mut table = self.manager.0.table.lock().expect("lock is poisoned");
+        table.held.retain(|(id, _)| *id != self.id);
+        drop(table);
+
+        self.manager.0.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn lock_range_overlaps() {
+        let range = |start: Bound<&str>, end: Bound<&str>| -> KeyRange {
+            (to_owned_bound(start), to_owned_bound(end))
+        };
+
+        let a_to_c = range(Bound::Included("a"), Bound::Excluded("c"));
+        let c_to_e = range(Bound::Included("c"), Bound::Included("e"));
+        let b = range(Bound::Included("b"), Bound::Included("b"));
+        let all = range(Bound::Unbounded, Bound::Unbounded);
+
+        assert!(!overlaps(&a_to_c, &c_to_e));
+        assert!(overlaps(&a_to_c, &b));
+        assert!(!overlaps(&c_to_e, &b));
+        assert!(overlaps(&all, &b));
+        assert!(overlaps(&b, &b));
+    }
+}
@@ -0,0 +1,47 @@
+use super::PartitionHandle;
+use std::path::Path;
+
+impl PartitionHandle {
+    /// Imports all items of a [`sled`](https://crates.io/crates/sled) database's default tree
+    /// into this partition, returning the amount of imported items.
+    ///
+    /// Items are read in key order, and written through the regular write path,
+    /// so the import is crash-safe, but not atomic.
+    ///
+    /// The sled database must not be opened by another process.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs (in either database),
+    /// if `path` is not a sled database, or if a key or value is too large.
+    pub fn import_from_sled<P: AsRef<Path>>(&self, path: P) -> crate::Result<u64> {
+        let path = path.as_ref();
+
+        // NOTE: sled::open creates an empty database if there is none,
+        // which would silently import nothing and leave sled files behind
+        if !path.join("conf").try_exists()? {
+            // TODO: dedicated error variant in 2.0.0
+            return Err(crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no sled database found at {path:?}"),
+            )));
+        }
+
+        let db = sled::open(path).map_err(std::io::Error::from)?;
+
+        let mut count = 0;
+
+        for item in db.iter() {
+            let (key, value) = item.map_err(std::io::Error::from)?;
+            self.insert(key, value)?;
+            count += 1;
+        }
+
+        log::info!(
+            "Imported {count} items from sled into partition {:?}",
+            self.name
+        );
+
+        Ok(count)
+    }
+}
@@ -1,23 +1,44 @@
 pub mod config;
+mod export;
+mod fnv;
+pub mod interceptor;
+pub mod lock;
 pub mod name;
+pub mod scope;
+pub mod sharded;
+pub mod watch;
+
+#[cfg(feature = "sled")]
+mod migrate;
 
 use crate::{
-    batch::{item::Item as BatchItem, PartitionKey},
+    batch::{
+        item::{check_key_value_size, Item as BatchItem},
+        PartitionKey,
+    },
+    commit_pipeline::CommitPipeline,
     compaction::manager::CompactionManager,
     config::Config as KeyspaceConfig,
+    diff::Diff,
+    event::{CompactionReason, Event, EventBus, StallReason},
     file::{PARTITIONS_FOLDER, PARTITION_DELETED_MARKER},
     flush::manager::{FlushManager, Task as FlushTask},
     journal::{
         manager::{JournalManager, PartitionSeqNo},
+        shard::JournalShard,
         Journal,
     },
     keyspace::Partitions,
+    slow_op::{ObservedScan, SlowOp},
     write_buffer_manager::WriteBufferManager,
     Error, Keyspace,
 };
 use config::CreateOptions;
+use fnv::Fnv128;
+use interceptor::WriteInterceptor;
+use lock::{LockManager, RangeLock};
 use lsm_tree::{
-    compaction::CompactionStrategy, KvPair, SequenceNumberCounter, Snapshot, Tree as LsmTree,
+    compaction::CompactionStrategy, KvPair, SeqNo, Snapshot, Tree as LsmTree, ValueType,
 };
 use std::{
     collections::HashMap,
@@ -30,6 +51,7 @@ use std::{
     time::Duration,
 };
 use std_semaphore::Semaphore;
+use watch::{WatchEvent, WatchReceiver, Watchers};
 
 #[allow(clippy::module_name_repetitions)]
 pub struct PartitionHandleInner {
@@ -43,7 +65,7 @@ pub struct PartitionHandleInner {
     pub(crate) journal: Arc<Journal>,
     pub(crate) partitions: Arc<RwLock<Partitions>>,
     pub(crate) compaction_manager: CompactionManager,
-    pub(crate) seqno: SequenceNumberCounter,
+    pub(crate) commit_pipeline: CommitPipeline,
     pub(crate) write_buffer_manager: WriteBufferManager,
     pub(crate) is_deleted: AtomicBool,
     pub(crate) is_poisoned: Arc<AtomicBool>,
@@ -55,6 +77,17 @@ pub struct PartitionHandleInner {
     pub(crate) max_memtable_size: AtomicU32,
 
     pub(crate) compaction_strategy: RwLock<Arc<dyn CompactionStrategy + Send + Sync>>,
+
+    pub(crate) write_interceptor: RwLock<Option<Arc<dyn WriteInterceptor + Send + Sync>>>,
+
+    /// Key ranges that are locked by users
+    pub(crate) locks: LockManager,
+
+    /// Subscribers that are notified about writes
+    pub(crate) watchers: Watchers,
+
+    /// Subscribers of the keyspace's background events
+    pub(crate) events: EventBus,
 }
 
 impl Drop for PartitionHandleInner {
@@ -119,6 +152,46 @@ impl PartitionHandle {
         *lock = strategy;
     }
 
+    /// Sets (or unsets) the write interceptor, which may reject writes before they
+    /// are appended to the journal.
+    ///
+    /// Default = None
+    pub fn set_write_interceptor(
+        &self,
+        interceptor: Option<Arc<dyn WriteInterceptor + Send + Sync>>,
+    ) {
+        let mut lock = self.write_interceptor.write().expect("lock is poisoned");
+        *lock = interceptor;
+    }
+
+    /// Runs the keyspace's key validator and the write interceptor, if any.
+    ///
+    /// A rejected key is reported as [`std::io::ErrorKind::InvalidInput`],
+    /// a rejected write as [`std::io::ErrorKind::PermissionDenied`].
+    pub(crate) fn intercept_write(
+        &self,
+        key: &[u8],
+        value_size: usize,
+        value_type: ValueType,
+    ) -> crate::Result<()> {
+        if let Some(validator) = &self.keyspace_config.key_validator {
+            // TODO: dedicated error variant in 2.0.0
+            validator(&self.name, key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        }
+
+        let lock = self.write_interceptor.read().expect("lock is poisoned");
+
+        if let Some(interceptor) = &*lock {
+            // TODO: dedicated error variant in 2.0.0
+            interceptor
+                .intercept(key, value_size, value_type == ValueType::Tombstone)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::PermissionDenied, e))?;
+        }
+
+        Ok(())
+    }
+
     /// Sets the maximum memtable size.
     ///
     /// Default = 8 MiB
@@ -160,9 +233,13 @@ impl PartitionHandle {
             journal_manager: keyspace.journal_manager.clone(),
             journal: keyspace.journal.clone(),
             compaction_manager: keyspace.compaction_manager.clone(),
-            seqno: keyspace.seqno.clone(),
+            commit_pipeline: keyspace.commit_pipeline.clone(),
             tree,
             compaction_strategy: RwLock::new(Arc::new(super::compaction::Levelled::default())),
+            write_interceptor: RwLock::default(),
+            locks: LockManager::default(),
+            watchers: Watchers::default(),
+            events: keyspace.events.clone(),
             max_memtable_size: (8 * 1_024 * 1_024).into(),
             write_buffer_manager: keyspace.write_buffer_manager.clone(),
             is_deleted: AtomicBool::default(),
@@ -221,7 +298,7 @@ impl PartitionHandle {
     #[must_use]
     #[allow(clippy::iter_not_returning_iterator)]
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
-        self.tree.iter().map(|item| Ok(item?))
+        self.observe_scan(self.tree.iter().map(|item| Ok(item?)))
     }
 
     /// Returns an iterator over a range of items.
@@ -251,7 +328,168 @@ impl PartitionHandle {
         &'a self,
         range: R,
     ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
-        self.tree.range(range).map(|item| Ok(item?))
+        self.observe_scan(self.tree.range(range).map(|item| Ok(item?)))
+    }
+
+    /// Computes a content hash over a range of items.
+    ///
+    /// Two partitions (or replicas of a partition) with the same items in that range
+    /// will return the same hash, so ranges can be compared cheaply, and ranges that differ
+    /// can be split recursively, like in a Merkle tree, to find the divergent items.
+    ///
+    /// The range is read from a snapshot at the current visible seqno, which is
+    /// returned together with the hash, so the hash stays consistent under concurrent writes.
+    ///
+    /// The hash is a 128-bit FNV-1a over all length-prefixed keys and values,
+    /// so it is not cryptographically secure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    /// let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+    ///
+    /// a.insert("a", "abc")?;
+    /// b.insert("a", "abc")?;
+    /// assert_eq!(a.hash_range("a"..)?.0, b.hash_range("a"..)?.0);
+    ///
+    /// b.insert("b", "def")?;
+    /// assert_eq!(a.hash_range("a".."b")?.0, b.hash_range("a".."b")?.0);
+    /// assert_ne!(a.hash_range("a"..)?.0, b.hash_range("a"..)?.0);
+    ///
+    /// // NOTE: The hash was computed at that seqno
+    /// let (hash, seqno) = b.hash_range("a"..)?;
+    /// b.insert("c", "ghi")?;
+    /// assert_ne!(hash, b.hash_range("a"..)?.0);
+    /// assert!(b.snapshot_at(seqno).get("c")?.is_none());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn hash_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> crate::Result<(u128, crate::Instant)> {
+        let seqno = self.commit_pipeline.visible_seqno();
+        let mut hasher = Fnv128::new();
+
+        for item in self.snapshot_at(seqno).range(range) {
+            let (key, value) = item?;
+
+            // NOTE: Length-prefix, so moving bytes between key and value changes the hash
+            hasher.update(&(key.len() as u64).to_be_bytes());
+            hasher.update(&key);
+            hasher.update(&(value.len() as u64).to_be_bytes());
+            hasher.update(&value);
+        }
+
+        Ok((hasher.finish(), seqno))
+    }
+
+    /// Subscribes to all writes of keys starting with `prefix`.
+    ///
+    /// Events are sent once the write was applied. Use an empty prefix to watch the entire partition.
+    ///
+    /// Writes to different journal shards are applied concurrently, so the delivery
+    /// order of concurrent writes is unspecified. Consumers that apply events to
+    /// other state (e.g. a cache) need to order them by their `seqno`.
+    /// Events of writes done by a single thread, and of a single batch, arrive in order.
+    ///
+    /// Events are buffered in an unbounded channel, so the receiver should be drained
+    /// regularly. Dropping the receiver cancels the subscription.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// use fjall::WatchEvent;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let events = partition.watch_prefix("user#");
+    ///
+    /// partition.insert("user#1", "abc")?;
+    /// partition.insert("post#1", "abc")?;
+    /// partition.remove("user#1")?;
+    ///
+    /// assert!(matches!(events.try_recv(), Ok(WatchEvent::Insert { .. })));
+    /// assert!(matches!(events.try_recv(), Ok(WatchEvent::Remove { .. })));
+    /// assert!(events.try_recv().is_err());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    pub fn watch_prefix<K: AsRef<[u8]>>(&self, prefix: K) -> WatchReceiver {
+        self.watchers.subscribe(prefix.as_ref().into())
+    }
+
+    /// Exclusively locks a single key, waiting up to `timeout` for conflicting locks to be released.
+    ///
+    /// See [`PartitionHandle::lock_range`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the lock could not be acquired in time,
+    /// as an [`Error::Io`] of kind [`std::io::ErrorKind::TimedOut`].
+    pub fn lock_key<K: AsRef<[u8]>>(&self, key: K, timeout: Duration) -> crate::Result<RangeLock> {
+        let key = key.as_ref();
+        self.locks.lock(key..=key, timeout)
+    }
+
+    /// Exclusively locks a key range, waiting up to `timeout` for overlapping locks to be released.
+    ///
+    /// Locks are advisory: they do not block reads or writes, only other lock calls
+    /// on this partition, so they can be used to build pessimistic transactions.
+    /// The lock is released when the returned guard is dropped.
+    ///
+    /// Locks are not reentrant, and there is no deadlock detection: a thread that
+    /// locks a range (or key) overlapping a lock it already holds, e.g. calling
+    /// [`PartitionHandle::lock_key`] inside its own `lock_range`, waits for itself until
+    /// the timeout expires. Deadlocks between threads are only resolved by the timeout as well.
+    ///
+    /// Use [`Duration::MAX`] to wait forever, but note that a deadlock then never resolves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// # use std::time::Duration;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let timeout = Duration::from_millis(10);
+    ///
+    /// let lock = partition.lock_range("a".."c", timeout)?;
+    /// assert!(partition.lock_key("c", timeout).is_ok());
+    /// assert!(matches!(
+    ///     partition.lock_key("b", timeout),
+    ///     Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::TimedOut,
+    /// ));
+    ///
+    /// drop(lock);
+    /// assert!(partition.lock_key("b", timeout).is_ok());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the lock could not be acquired in time,
+    /// as an [`Error::Io`] of kind [`std::io::ErrorKind::TimedOut`].
+    pub fn lock_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+        timeout: Duration,
+    ) -> crate::Result<RangeLock> {
+        self.locks.lock(range, timeout)
     }
 
     /// Returns an iterator over a prefixed set of items.
@@ -281,7 +519,89 @@ impl PartitionHandle {
         &'a self,
         prefix: K,
     ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
-        self.tree.prefix(prefix).map(|item| Ok(item?))
+        self.observe_scan(self.tree.prefix(prefix).map(|item| Ok(item?)))
+    }
+
+    /// Reports the scan to the slow operation observer, if configured
+    fn observe_scan<I: DoubleEndedIterator>(&self, iter: I) -> ObservedScan<I> {
+        let observer = self
+            .keyspace_config
+            .slow_op_observer
+            .clone()
+            .map(|observer| (observer, self.name.clone()));
+
+        ObservedScan::new(iter, observer)
+    }
+
+    /// Returns an iterator over items of a prefix, limited to a time range.
+    ///
+    /// Keys need to be built using [`crate::key::with_timestamp`], so the
+    /// prefix is followed by a big-endian timestamp.
+    ///
+    /// Only keys that are exactly the prefix followed by the timestamp are returned,
+    /// so longer keys that happen to start with the prefix (e.g. `"sensor-1#a"` for
+    /// the prefix `"sensor-1#"`) are skipped, even if they fall into the key range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// use fjall::key::with_timestamp;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert(with_timestamp("sensor-1#", 100), "a")?;
+    /// partition.insert(with_timestamp("sensor-1#", 200), "b")?;
+    /// partition.insert(with_timestamp("sensor-1#", 300), "c")?;
+    /// partition.insert(with_timestamp("sensor-2#", 200), "d")?;
+    ///
+    /// partition.insert(with_timestamp("sensor-1#a", 200), "e")?;
+    ///
+    /// assert_eq!(2, partition.prefix_range("sensor-1#", 150..).count());
+    /// assert_eq!(1, partition.prefix_range("sensor-2#", ..=200).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn prefix_range<K: AsRef<[u8]>, R: RangeBounds<u64>>(
+        &self,
+        prefix: K,
+        time_range: R,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        let key_len = prefix.as_ref().len() + std::mem::size_of::<u64>();
+
+        self.range(crate::key::timestamp_range(prefix.as_ref(), &time_range))
+            .filter(move |item| !matches!(item, Ok((key, _)) if key.len() != key_len))
+    }
+
+    /// Returns a view into the partition that only sees keys starting with the given prefix.
+    ///
+    /// The prefix is transparently prepended to keys passed into the scope,
+    /// and stripped from keys returned by it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let scope = partition.scope("tenant-a#");
+    /// scope.insert("a", "abc")?;
+    ///
+    /// assert!(scope.contains_key("a")?);
+    /// assert!(partition.contains_key("tenant-a#a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn scope<K: AsRef<[u8]>>(&self, prefix: K) -> scope::Scope {
+        scope::Scope::new(self.clone(), prefix.as_ref())
     }
 
     /// Approximates the amount of items in the partition.
@@ -433,7 +753,57 @@ impl PartitionHandle {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn get<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<Option<lsm_tree::UserValue>> {
-        Ok(self.tree.get(key)?)
+        let Some(observer) = &self.keyspace_config.slow_op_observer else {
+            return Ok(self.tree.get(key)?);
+        };
+
+        let key = key.as_ref();
+
+        Ok(observer.time(
+            || self.tree.get(key),
+            |duration| SlowOp::Get {
+                partition: self.name.clone(),
+                key: key.into(),
+                duration,
+            },
+        )?)
+    }
+
+    /// Retrieves the latest version of an item, including tombstones.
+    ///
+    /// Returns the item's sequence number, whether it is a tombstone, and its value
+    /// (which is empty for tombstones). Useful for replication or debugging,
+    /// where deletions and versions need to be observed.
+    ///
+    /// Tombstones may disappear once they have been compacted away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.remove("a")?;
+    ///
+    /// let (_seqno, is_tombstone, _value) = partition.get_raw("a")?.expect("should exist");
+    /// assert!(is_tombstone);
+    /// assert_eq!(None, partition.get("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get_raw<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+    ) -> crate::Result<Option<(SeqNo, bool, lsm_tree::UserValue)>> {
+        let item = self.tree.get_internal_entry(key, false, None)?;
+        Ok(item.map(|item| (item.seqno, item.is_tombstone(), item.value)))
     }
 
     /// Returns the first key-value pair in the partition.
@@ -539,6 +909,8 @@ impl PartitionHandle {
             map
         };
 
+        fail_point!(self.keyspace_config, "journal::before_rotate");
+
         journal_manager.rotate_journal(&mut journal, seqno_map)?;
 
         log::trace!("partition: acquiring flush manager lock");
@@ -563,7 +935,27 @@ impl PartitionHandle {
         Ok(true)
     }
 
+    /// Emits a stall event, and returns the moment the stall started
+    fn start_stall(&self, reason: StallReason) -> std::time::Instant {
+        self.events.emit(|| Event::WriteStallStarted {
+            partition: self.name.clone(),
+            reason,
+        });
+
+        std::time::Instant::now()
+    }
+
+    fn finish_stall(&self, reason: StallReason, start: std::time::Instant) {
+        self.events.emit(|| Event::WriteStallFinished {
+            partition: self.name.clone(),
+            reason,
+            duration: start.elapsed(),
+        });
+    }
+
     fn check_journal_size(&self) {
+        let mut stall_start = None;
+
         loop {
             let bytes = self
                 .journal_manager
@@ -576,6 +968,7 @@ impl PartitionHandle {
                     log::info!(
                         "partition: write stall because 90% journal threshold has been reached"
                     );
+                    stall_start.get_or_insert_with(|| self.start_stall(StallReason::JournalSize));
                     std::thread::sleep(std::time::Duration::from_millis(500));
                 }
 
@@ -583,16 +976,29 @@ impl PartitionHandle {
             }
 
             log::debug!("partition: write halt because of too many journals");
+            stall_start.get_or_insert_with(|| self.start_stall(StallReason::JournalSize));
             std::thread::sleep(std::time::Duration::from_millis(100)); // TODO: maybe exponential backoff
         }
+
+        if let Some(start) = stall_start {
+            self.finish_stall(StallReason::JournalSize, start);
+        }
     }
 
     fn check_write_halt(&self) {
+        let mut stall_start = None;
+
         while self.tree.first_level_segment_count() > 24 {
             log::info!("Halting writes until L0 is cleared up...");
-            self.compaction_manager.notify(self.clone());
+            stall_start.get_or_insert_with(|| self.start_stall(StallReason::L0Segments));
+            self.compaction_manager
+                .notify(self.clone(), CompactionReason::L0Segments);
             std::thread::sleep(Duration::from_millis(1_000));
         }
+
+        if let Some(start) = stall_start {
+            self.finish_stall(StallReason::L0Segments, start);
+        }
     }
 
     fn check_write_stall(&self) {
@@ -600,16 +1006,48 @@ impl PartitionHandle {
 
         if seg_count > 20 {
             log::info!("Stalling writes, many segments in L0...");
-            self.compaction_manager.notify(self.clone());
+            self.compaction_manager
+                .notify(self.clone(), CompactionReason::L0Segments);
+
+            let start = self.start_stall(StallReason::L0Segments);
 
             let ms = if seg_count > 22 { 500 } else { 100 };
             std::thread::sleep(Duration::from_millis(ms));
+
+            self.finish_stall(StallReason::L0Segments, start);
         }
     }
 
+    /// Flushes sealed memtables and runs queued compactions in the calling thread.
+    ///
+    /// Used when background threads are disabled.
+    fn run_inline_maintenance(&self) {
+        crate::flush::worker::run(
+            &self.flush_manager,
+            &self.journal_manager,
+            &self.compaction_manager,
+            &self.write_buffer_manager,
+            self.keyspace_config.flush_workers_count.max(1),
+            true,
+        );
+
+        crate::compaction::worker::run_until_empty(&self.compaction_manager);
+    }
+
     pub(crate) fn check_memtable_overflow(&self, size: u32) -> crate::Result<()> {
         use std::sync::atomic::Ordering::Acquire;
 
+        // NOTE: Without background threads, nobody would ever resolve a write stall,
+        // so do the work right here instead
+        if !self.keyspace_config.background_threads {
+            if size > self.max_memtable_size.load(Acquire) {
+                self.rotate_memtable()?;
+                self.run_inline_maintenance();
+            }
+
+            return Ok(());
+        }
+
         if size > self.max_memtable_size.load(Acquire) {
             self.rotate_memtable()?;
             self.check_journal_size();
@@ -622,7 +1060,20 @@ impl PartitionHandle {
     }
 
     pub(crate) fn check_write_buffer_size(&self, initial_size: u64) {
+        if !self.keyspace_config.background_threads {
+            if initial_size > self.keyspace_config.max_write_buffer_size_in_bytes {
+                if let Err(e) = self.rotate_memtable() {
+                    log::error!("Failed to rotate memtable: {e:?}");
+                }
+                self.run_inline_maintenance();
+            }
+
+            return;
+        }
+
         if initial_size > self.keyspace_config.max_write_buffer_size_in_bytes {
+            let mut stall_start = None;
+
             loop {
                 let bytes = self.write_buffer_manager.get();
 
@@ -633,14 +1084,21 @@ impl PartitionHandle {
                         log::info!(
                             "partition: write stall because 90% write buffer threshold has been reached"
                         );
+                        stall_start
+                            .get_or_insert_with(|| self.start_stall(StallReason::WriteBufferSize));
                         std::thread::sleep(std::time::Duration::from_millis(500));
                     }
                     break;
                 }
 
                 log::info!("partition: write halt because of write buffer saturation");
+                stall_start.get_or_insert_with(|| self.start_stall(StallReason::WriteBufferSize));
                 std::thread::sleep(std::time::Duration::from_millis(100));
             }
+
+            if let Some(start) = stall_start {
+                self.finish_stall(StallReason::WriteBufferSize, start);
+            }
         }
     }
 
@@ -653,7 +1111,7 @@ impl PartitionHandle {
     /// Opens a snapshot of this partition.
     #[must_use]
     pub fn snapshot(&self) -> Snapshot {
-        self.snapshot_at(self.seqno.get())
+        self.snapshot_at(self.commit_pipeline.visible_seqno())
     }
 
     /// Opens a snapshot of this partition with a given sequence number.
@@ -662,9 +1120,44 @@ impl PartitionHandle {
         self.tree.snapshot(seqno)
     }
 
+    /// Returns all keys that were added, removed or changed in `other`, compared to this partition.
+    ///
+    /// To compare snapshots, or other key-value sequences, use [`crate::diff`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, DiffEntry, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let primary = keyspace.open_partition("primary", PartitionCreateOptions::default())?;
+    /// let replica = keyspace.open_partition("replica", PartitionCreateOptions::default())?;
+    ///
+    /// primary.insert("a", "abc")?;
+    /// replica.insert("a", "abc")?;
+    /// primary.insert("b", "def")?;
+    ///
+    /// let changes = primary.diff(&replica).collect::<fjall::Result<Vec<_>>>()?;
+    /// assert_eq!(1, changes.len());
+    /// assert!(matches!(changes[0], DiffEntry::Removed(..)));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn diff(
+        &self,
+        other: &Self,
+    ) -> Diff<
+        impl Iterator<Item = crate::Result<KvPair>>,
+        impl Iterator<Item = crate::Result<KvPair>>,
+    > {
+        crate::diff(self.iter(), other.iter())
+    }
+
     /// Inserts a key-value pair into the partition.
     ///
-    /// Keys may be up to 65536 bytes long, values up to 65536 bytes.
+    /// Keys may be up to 65535 bytes long, values up to 65535 bytes.
     /// Shorter keys and values result in better performance.
     ///
     /// If the key already exists, the item will be overwritten.
@@ -686,52 +1179,294 @@ impl PartitionHandle {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
     pub fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, key: K, value: V) -> crate::Result<()> {
+        self.insert_with_seqno(key, value)?;
+        Ok(())
+    }
+
+    /// Inserts a key-value pair into the partition, like [`PartitionHandle::insert`].
+    ///
+    /// Returns the seqno that was assigned to the write, so it can be correlated
+    /// with snapshots or durability watermarks later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let seqno = partition.insert_with_seqno("a", "abc")?;
+    ///
+    /// assert!(!partition.snapshot_at(seqno).contains_key("a")?);
+    /// assert!(partition.snapshot_at(seqno + 1).contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
+    pub fn insert_with_seqno<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> crate::Result<SeqNo> {
+        let key = key.as_ref();
         let value = value.as_ref();
 
-        // TODO: remove in 2.0.0
-        assert!(
-            u16::try_from(value.len()).is_ok(),
-            "Value should be 65535 bytes or less"
-        );
+        check_key_value_size(key, value)?;
+        self.check_writable()?;
 
-        if self.is_deleted.load(std::sync::atomic::Ordering::Relaxed) {
-            return Err(crate::Error::PartitionDeleted);
+        // IMPORTANT: Needs to run before locking the journal, see WriteInterceptor
+        self.intercept_write(key, value.len(), ValueType::Value)?;
+
+        let mut shard = self.journal.get_writer();
+        let (seqno, item_size, memtable_size) =
+            self.append_entry(&mut shard, key, value, ValueType::Value)?;
+        drop(shard);
+
+        self.finish_write(item_size, memtable_size)?;
+
+        Ok(seqno)
+    }
+
+    /// Inserts multiple key-value pairs into the partition.
+    ///
+    /// All items are written to the journal as a single batch and become visible
+    /// atomically, which is much faster than inserting items one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert_many((0u32..100).map(|x| (x.to_be_bytes(), "abc")))?;
+    ///
+    /// assert_eq!(100, partition.len()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if any key or value is too large.
+    /// In that case, nothing is written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any key is empty.
+    pub fn insert_many<K: AsRef<[u8]>, V: AsRef<[u8]>, I: IntoIterator<Item = (K, V)>>(
+        &self,
+        items: I,
+    ) -> crate::Result<()> {
+        let items = items
+            .into_iter()
+            .map(|(key, value)| {
+                BatchItem::new(
+                    self.name.clone(),
+                    key.as_ref(),
+                    value.as_ref(),
+                    ValueType::Value,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if items.is_empty() {
+            return Ok(());
         }
 
-        if self.is_poisoned.load(std::sync::atomic::Ordering::Relaxed) {
-            return Err(crate::Error::Poisoned);
+        for item in &items {
+            item.check_size()?;
+            self.intercept_write(&item.key, item.value.len(), item.value_type)?;
         }
 
+        self.check_writable()?;
+
         let mut shard = self.journal.get_writer();
 
-        let seqno = self.seqno.next();
+        // IMPORTANT: Lock the memtable, so the items become visible atomically
+        let memtable = self.tree.lock_active_memtable();
 
-        shard.writer.write(
-            &BatchItem {
-                key: key.as_ref().into(),
-                value: value.as_ref().into(),
-                partition: self.name.clone(),
-                value_type: lsm_tree::ValueType::Value,
-            },
-            seqno,
-        )?;
+        let ticket = self.commit_pipeline.begin();
+        let seqno = ticket.seqno();
+
+        let _ = shard
+            .writer
+            .write_batch(&items.iter().collect::<Vec<_>>(), seqno)?;
+        shard.should_sync = true;
+
+        fail_point!(self.keyspace_config, "journal::after_write");
+
+        let events = if self.watchers.is_active() {
+            items
+                .iter()
+                .map(|item| {
+                    WatchEvent::new(item.key.clone(), item.value.clone(), item.value_type, seqno)
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let mut batch_size = 0u64;
+
+        for item in items {
+            let (item_size, _) = memtable.insert(lsm_tree::Value {
+                key: item.key,
+                value: item.value,
+                seqno,
+                value_type: item.value_type,
+            });
+            batch_size += u64::from(item_size);
+        }
+
+        drop(memtable);
+        drop(ticket);
         drop(shard);
 
-        let (item_size, memtable_size) = self.tree.insert(key, value, seqno);
+        self.watchers.notify(&events);
 
-        let write_buffer_size = self.write_buffer_manager.allocate(u64::from(item_size));
+        let write_buffer_size = self.write_buffer_manager.allocate(batch_size);
 
-        self.check_memtable_overflow(memtable_size)?;
+        self.check_memtable_overflow(self.tree.active_memtable_size())?;
         self.check_write_buffer_size(write_buffer_size);
 
         Ok(())
     }
 
+    /// Inserts a key-value pair into the partition, returning the previous value.
+    ///
+    /// Unlike composing [`PartitionHandle::get`] and [`PartitionHandle::insert`],
+    /// the read and write are performed while holding the journal lock, so
+    /// no other write can slip in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// assert_eq!(None, partition.insert_fetch("a", "abc")?);
+    ///
+    /// let prev = partition.insert_fetch("a", "def")?;
+    /// assert_eq!(Some("abc".as_bytes().into()), prev);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
+    pub fn insert_fetch<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> crate::Result<Option<lsm_tree::UserValue>> {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        check_key_value_size(key, value)?;
+        self.check_writable()?;
+
+        // IMPORTANT: Needs to run before locking the journal, see WriteInterceptor
+        self.intercept_write(key, value.len(), ValueType::Value)?;
+
+        // NOTE: Fully lock the journal, so no other write can happen
+        // between reading the previous value and writing the new one
+        let mut journal = self.journal.full_lock();
+
+        let prev = self.get(key)?;
+
+        let shard = journal.first_mut().expect("journal should have shards");
+        let (_, item_size, memtable_size) =
+            self.append_entry(shard, key, value, ValueType::Value)?;
+        drop(journal);
+
+        self.finish_write(item_size, memtable_size)?;
+
+        Ok(prev)
+    }
+
+    /// Inserts a key-value pair into the partition, but only if the key does not exist yet.
+    ///
+    /// Returns `true` if the item was inserted.
+    ///
+    /// The existence check and the write are performed while holding the journal
+    /// lock, which makes this useful for "claim" patterns (locks, deduplication).
+    ///
+    /// If the key exists, the [`crate::WriteInterceptor`] is not called, unless the key
+    /// was written concurrently, after the interceptor was already called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// assert!(partition.insert_if_absent("lock", "worker-1")?);
+    /// assert!(!partition.insert_if_absent("lock", "worker-2")?);
+    ///
+    /// let item = partition.get("lock")?.expect("should exist");
+    /// assert_eq!(b"worker-1", &*item);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
+    pub fn insert_if_absent<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> crate::Result<bool> {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        check_key_value_size(key, value)?;
+        self.check_writable()?;
+
+        // NOTE: Check before asking the interceptor, so it does not see a write
+        // that is not going to happen
+        if self.contains_key(key)? {
+            return Ok(false);
+        }
+
+        // IMPORTANT: Needs to run before locking the journal, see WriteInterceptor
+        self.intercept_write(key, value.len(), ValueType::Value)?;
+
+        // NOTE: Fully lock the journal, so no other write can happen
+        // between checking for the key and writing it
+        let mut journal = self.journal.full_lock();
+
+        // NOTE: The key may have been written since the first check
+        if self.contains_key(key)? {
+            return Ok(false);
+        }
+
+        let shard = journal.first_mut().expect("journal should have shards");
+        let (_, item_size, memtable_size) =
+            self.append_entry(shard, key, value, ValueType::Value)?;
+        drop(journal);
+
+        self.finish_write(item_size, memtable_size)?;
+
+        Ok(true)
+    }
+
     /// Removes an item from the partition.
     ///
-    /// The key may be up to 65536 bytes long.
+    /// The key may be up to 65535 bytes long.
     /// Shorter keys result in better performance.
     ///
     /// # Examples
@@ -757,8 +1492,39 @@ impl PartitionHandle {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
+    /// Will return `Err` if an IO error occurs, or if the key is too large.
     pub fn remove<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<()> {
+        self.remove_with_seqno(key)?;
+        Ok(())
+    }
+
+    /// Removes an item from the partition, like [`PartitionHandle::remove`].
+    ///
+    /// Returns the seqno that was assigned to the write.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key is too large.
+    pub fn remove_with_seqno<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<SeqNo> {
+        let key = key.as_ref();
+
+        check_key_value_size(key, &[])?;
+        self.check_writable()?;
+
+        // IMPORTANT: Needs to run before locking the journal, see WriteInterceptor
+        self.intercept_write(key, 0, ValueType::Tombstone)?;
+
+        let mut shard = self.journal.get_writer();
+        let (seqno, item_size, memtable_size) =
+            self.append_entry(&mut shard, key, &[], ValueType::Tombstone)?;
+        drop(shard);
+
+        self.finish_write(item_size, memtable_size)?;
+
+        Ok(seqno)
+    }
+
+    fn check_writable(&self) -> crate::Result<()> {
         if self.is_deleted.load(std::sync::atomic::Ordering::Relaxed) {
             return Err(crate::Error::PartitionDeleted);
         }
@@ -767,24 +1533,54 @@ impl PartitionHandle {
             return Err(crate::Error::Poisoned);
         }
 
-        let mut shard = self.journal.get_writer();
+        Ok(())
+    }
 
-        let seqno = self.seqno.next();
+    /// Writes an entry to the journal, then to the active memtable.
+    ///
+    /// Returns the seqno, the item size and the new memtable size.
+    ///
+    /// IMPORTANT: The memtable is written while the journal shard is still locked,
+    /// so holding the full journal lock guarantees all previous writes are visible.
+    fn append_entry(
+        &self,
+        shard: &mut JournalShard,
+        key: &[u8],
+        value: &[u8],
+        value_type: ValueType,
+    ) -> crate::Result<(SeqNo, u32, u32)> {
+        let ticket = self.commit_pipeline.begin();
+        let seqno = ticket.seqno();
 
-        /* let bytes_written = */
         shard.writer.write(
             &BatchItem {
-                key: key.as_ref().into(),
-                value: [].into(),
+                key: key.into(),
+                value: value.into(),
                 partition: self.name.clone(),
-                value_type: lsm_tree::ValueType::Tombstone,
+                value_type,
             },
             seqno,
         )?;
-        drop(shard);
+        shard.should_sync = true;
+
+        fail_point!(self.keyspace_config, "journal::after_write");
 
-        let (item_size, memtable_size) = self.tree.remove(key, seqno);
+        let (item_size, memtable_size) = match value_type {
+            ValueType::Value => self.tree.insert(key, value, seqno),
+            ValueType::Tombstone => self.tree.remove(key, seqno),
+        };
+        drop(ticket);
+
+        if self.watchers.is_active() {
+            self.watchers
+                .notify(&[WatchEvent::new(key.into(), value.into(), value_type, seqno)]);
+        }
+
+        Ok((seqno, item_size, memtable_size))
+    }
 
+    /// Accounts for a written item and checks for write stalls.
+    fn finish_write(&self, item_size: u32, memtable_size: u32) -> crate::Result<()> {
         let write_buffer_size = self.write_buffer_manager.allocate(u64::from(item_size));
 
         self.check_memtable_overflow(memtable_size)?;
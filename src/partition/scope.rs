@@ -0,0 +1,449 @@
+use crate::{Batch, PartitionHandle};
+use lsm_tree::{KvPair, UserKey, UserValue};
+use std::ops::{Bound, RangeBounds};
+
+/// Returns the smallest key that is larger than every key starting with the prefix.
+///
+/// Returns `None` if there is no such key (empty prefix, or only 0xFF bytes).
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+
+    None
+}
+
+/// A view into a partition that only sees keys starting with a given prefix
+///
+/// The prefix is transparently prepended to keys passed into the scope,
+/// and stripped from keys returned by it. This gives cheap namespacing
+/// (e.g. per tenant) without creating a partition per namespace.
+///
+/// Scopes are created using [`PartitionHandle::scope`], and are cheap to clone.
+#[derive(Clone)]
+pub struct Scope {
+    partition: PartitionHandle,
+    prefix: UserKey,
+}
+
+impl Scope {
+    pub(crate) fn new(partition: PartitionHandle, prefix: &[u8]) -> Self {
+        Self {
+            partition,
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Returns the underlying partition.
+    #[must_use]
+    pub fn partition(&self) -> &PartitionHandle {
+        &self.partition
+    }
+
+    /// Returns the key prefix of this scope.
+    #[must_use]
+    pub fn key_prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    /// Creates a nested scope, whose prefix is appended to the prefix of this scope.
+    #[must_use]
+    pub fn scope<K: AsRef<[u8]>>(&self, prefix: K) -> Self {
+        Self::new(self.partition.clone(), &self.full_key(prefix))
+    }
+
+    /// Prepends the scope prefix to a key.
+    pub(crate) fn full_key<K: AsRef<[u8]>>(&self, key: K) -> Vec<u8> {
+        let key = key.as_ref();
+
+        let mut full_key = Vec::with_capacity(self.prefix.len() + key.len());
+        full_key.extend_from_slice(&self.prefix);
+        full_key.extend_from_slice(key);
+        full_key
+    }
+
+    /// Strips the scope prefix from items read from the partition.
+    pub(crate) fn strip_prefix(
+        prefix_len: usize,
+    ) -> impl Fn(crate::Result<KvPair>) -> crate::Result<KvPair> {
+        move |item| {
+            let (key, value) = item?;
+            let key = key.get(prefix_len..).unwrap_or_default().into();
+            Ok((key, value))
+        }
+    }
+
+    /// Converts a range inside the scope into a range over the partition.
+    pub(crate) fn full_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: &R,
+    ) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+        let start = match range.start_bound() {
+            Bound::Included(key) => Bound::Included(self.full_key(key)),
+            Bound::Excluded(key) => Bound::Excluded(self.full_key(key)),
+            Bound::Unbounded => Bound::Included(self.prefix.to_vec()),
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(key) => Bound::Included(self.full_key(key)),
+            Bound::Excluded(key) => Bound::Excluded(self.full_key(key)),
+            Bound::Unbounded => {
+                prefix_upper_bound(&self.prefix).map_or(Bound::Unbounded, Bound::Excluded)
+            }
+        };
+
+        (start, end)
+    }
+
+    /// Returns an iterator that scans through the entire scope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let tenant_a = partition.scope("tenant-a#");
+    /// let tenant_b = partition.scope("tenant-b#");
+    ///
+    /// tenant_a.insert("a", "abc")?;
+    /// tenant_a.insert("b", "abc")?;
+    /// tenant_b.insert("a", "abc")?;
+    ///
+    /// assert_eq!(2, tenant_a.iter().count());
+    /// assert_eq!(3, partition.iter().count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    #[must_use]
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        self.range::<&[u8], _>(..)
+    }
+
+    /// Returns an iterator over a range of items inside the scope.
+    ///
+    /// Range bounds are relative to the scope, and so are returned keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let scope = partition.scope("tenant-a#");
+    /// scope.insert("a", "abc")?;
+    /// scope.insert("f", "abc")?;
+    /// scope.insert("g", "abc")?;
+    ///
+    /// let (key, _) = scope.range("b"..).next().expect("should exist")?;
+    /// assert_eq!(&*key, b"f");
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        self.partition
+            .range(self.full_range(&range))
+            .map(Self::strip_prefix(self.prefix.len()))
+    }
+
+    /// Returns an iterator over a prefixed set of items inside the scope.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn prefix<K: AsRef<[u8]>>(
+        &self,
+        prefix: K,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        self.partition
+            .prefix(self.full_key(prefix))
+            .map(Self::strip_prefix(self.prefix.len()))
+    }
+
+    /// Scans the entire scope, returning the amount of items.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn len(&self) -> crate::Result<usize> {
+        let mut count = 0;
+
+        for item in self.iter() {
+            let _ = item?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Returns `true` if the scope is empty.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn is_empty(&self) -> crate::Result<bool> {
+        Ok(self.first_key_value()?.is_none())
+    }
+
+    /// Returns `true` if the scope contains the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<bool> {
+        self.partition.contains_key(self.full_key(key))
+    }
+
+    /// Retrieves an item from the scope.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<Option<UserValue>> {
+        self.partition.get(self.full_key(key))
+    }
+
+    /// Returns the first key-value pair in the scope.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn first_key_value(&self) -> crate::Result<Option<KvPair>> {
+        self.iter().next().transpose()
+    }
+
+    /// Returns the last key-value pair in the scope.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn last_key_value(&self) -> crate::Result<Option<KvPair>> {
+        self.iter().next_back().transpose()
+    }
+
+    /// Inserts a key-value pair into the scope.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
+    pub fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, key: K, value: V) -> crate::Result<()> {
+        self.partition.insert(self.full_key(key), value)
+    }
+
+    /// Removes an item from the scope.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key is too large.
+    pub fn remove<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<()> {
+        self.partition.remove(self.full_key(key))
+    }
+
+    /// Opens a snapshot of this scope.
+    #[must_use]
+    pub fn snapshot(&self) -> ScopeSnapshot {
+        self.snapshot_at(self.partition.commit_pipeline.visible_seqno())
+    }
+
+    /// Opens a snapshot of this scope with a given sequence number.
+    ///
+    /// Use [`crate::Keyspace::instant`] to read multiple scopes (or partitions)
+    /// at the same moment in time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let tenant_a = partition.scope("tenant-a#");
+    /// let tenant_b = partition.scope("tenant-b#");
+    ///
+    /// tenant_a.insert("a", "abc")?;
+    /// tenant_b.insert("a", "abc")?;
+    ///
+    /// let instant = keyspace.instant();
+    /// let snapshot_a = tenant_a.snapshot_at(instant);
+    /// let snapshot_b = tenant_b.snapshot_at(instant);
+    ///
+    /// tenant_a.insert("b", "abc")?;
+    /// tenant_b.remove("a")?;
+    ///
+    /// assert_eq!(1, snapshot_a.range("a"..).count());
+    /// assert!(snapshot_b.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn snapshot_at(&self, seqno: crate::Instant) -> ScopeSnapshot {
+        ScopeSnapshot {
+            scope: self.clone(),
+            instant: seqno,
+        }
+    }
+
+    /// Inserts a key-value pair of this scope into a write batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let scope = partition.scope("tenant-a#");
+    ///
+    /// let mut batch = keyspace.batch();
+    /// scope.batch_insert(&mut batch, "a", "abc");
+    /// scope.batch_insert(&mut batch, "b", "abc");
+    /// batch.commit()?;
+    ///
+    /// assert_eq!(2, scope.len()?);
+    /// assert!(partition.contains_key("tenant-a#a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    pub fn batch_insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        batch: &mut Batch,
+        key: K,
+        value: V,
+    ) {
+        batch.insert(&self.partition, self.full_key(key), value);
+    }
+
+    /// Adds a tombstone marker for a key of this scope into a write batch.
+    pub fn batch_remove<K: AsRef<[u8]>>(&self, batch: &mut Batch, key: K) {
+        batch.remove(&self.partition, self.full_key(key));
+    }
+}
+
+/// A read-only view of a [`Scope`] at a given moment in time
+///
+/// Created using [`Scope::snapshot`] or [`Scope::snapshot_at`].
+#[derive(Clone)]
+pub struct ScopeSnapshot {
+    scope: Scope,
+    instant: crate::Instant,
+}
+
+impl ScopeSnapshot {
+    /// Returns the moment in time this snapshot reads at.
+    #[must_use]
+    pub fn instant(&self) -> crate::Instant {
+        self.instant
+    }
+
+    /// Retrieves an item from the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<Option<UserValue>> {
+        Ok(self
+            .scope
+            .partition
+            .snapshot_at(self.instant)
+            .get(self.scope.full_key(key))?)
+    }
+
+    /// Returns `true` if the snapshot contains the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<bool> {
+        self.get(key).map(|x| x.is_some())
+    }
+
+    /// Returns an iterator that scans through the entire scope, as seen by the snapshot.
+    #[must_use]
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        self.range::<&[u8], _>(..)
+    }
+
+    /// Returns an iterator over a range of items, as seen by the snapshot.
+    ///
+    /// Range bounds are relative to the scope, and so are returned keys.
+    pub fn range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        self.scope
+            .partition
+            .tree
+            .create_range(&self.scope.full_range(&range), Some(self.instant), None)
+            .map(|item| Ok(item?))
+            .map(Scope::strip_prefix(self.scope.prefix.len()))
+    }
+
+    /// Returns an iterator over a prefixed set of items, as seen by the snapshot.
+    pub fn prefix<K: AsRef<[u8]>>(
+        &self,
+        prefix: K,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
+        self.scope
+            .partition
+            .tree
+            .create_prefix(self.scope.full_key(prefix), Some(self.instant), None)
+            .map(|item| Ok(item?))
+            .map(Scope::strip_prefix(self.scope.prefix.len()))
+    }
+
+    /// Returns the first key-value pair in the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn first_key_value(&self) -> crate::Result<Option<KvPair>> {
+        self.iter().next().transpose()
+    }
+
+    /// Returns the last key-value pair in the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn last_key_value(&self) -> crate::Result<Option<KvPair>> {
+        self.iter().next_back().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn scope_prefix_upper_bound() {
+        assert_eq!(Some(b"ac".to_vec()), prefix_upper_bound(b"ab"));
+        assert_eq!(Some(b"b".to_vec()), prefix_upper_bound(b"a\xFF"));
+        assert_eq!(None, prefix_upper_bound(b"\xFF\xFF"));
+        assert_eq!(None, prefix_upper_bound(b""));
+    }
+}
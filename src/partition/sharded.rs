@@ -0,0 +1,330 @@
+use crate::{
+    file::{rename_atomic, SHARD_MARKER},
+    Batch, PartitionHandle,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use lsm_tree::{KvPair, UserValue};
+use std::{
+    cmp::Reverse, collections::BinaryHeap, fs::File, io::Write, ops::RangeBounds, path::Path,
+};
+
+type BoxedIter = Box<dyn Iterator<Item = crate::Result<KvPair>>>;
+
+/// Loads the shard count that is stored in a shard's partition folder
+pub fn load_shard_count<P: AsRef<Path>>(path: P) -> crate::Result<Option<u64>> {
+    let path = path.as_ref().join(SHARD_MARKER);
+
+    if !path.try_exists()? {
+        return Ok(None);
+    }
+
+    let mut file = File::open(path)?;
+    Ok(Some(file.read_u64::<BigEndian>()?))
+}
+
+/// Durably stores the shard count in a shard's partition folder
+pub fn store_shard_count<P: AsRef<Path>>(path: P, shard_count: u64) -> crate::Result<()> {
+    let path = path.as_ref();
+    let tmp_path = path.join(format!("{SHARD_MARKER}.tmp"));
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_u64::<BigEndian>(shard_count)?;
+    file.flush()?;
+    file.sync_all()?;
+
+    // IMPORTANT: Rename, so the marker is never partially written
+    rename_atomic(&tmp_path, path.join(SHARD_MARKER))?;
+
+    Ok(())
+}
+
+/// Merges N sorted iterators into one sorted iterator
+struct MergeIter {
+    iters: Vec<BoxedIter>,
+    heap: BinaryHeap<Reverse<(lsm_tree::UserKey, usize)>>,
+    values: Vec<Option<UserValue>>,
+    is_initialized: bool,
+
+    /// Set after an error, because items of the failed iterator would be silently missing
+    is_fused: bool,
+}
+
+impl MergeIter {
+    fn new(iters: Vec<BoxedIter>) -> Self {
+        let values = iters.iter().map(|_| None).collect();
+
+        Self {
+            iters,
+            heap: BinaryHeap::new(),
+            values,
+            is_initialized: false,
+            is_fused: false,
+        }
+    }
+
+    /// Pulls the next item of the given iterator into the heap.
+    fn advance(&mut self, idx: usize) -> crate::Result<()> {
+        let Some(iter) = self.iters.get_mut(idx) else {
+            return Ok(());
+        };
+
+        if let Some(item) = iter.next() {
+            let (key, value) = item?;
+
+            if let Some(slot) = self.values.get_mut(idx) {
+                *slot = Some(value);
+            }
+
+            self.heap.push(Reverse((key, idx)));
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for MergeIter {
+    type Item = crate::Result<KvPair>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_fused {
+            return None;
+        }
+
+        if !self.is_initialized {
+            self.is_initialized = true;
+
+            for idx in 0..self.iters.len() {
+                if let Err(e) = self.advance(idx) {
+                    self.is_fused = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        let Reverse((key, idx)) = self.heap.pop()?;
+        let value = self.values.get_mut(idx)?.take()?;
+
+        if let Err(e) = self.advance(idx) {
+            self.is_fused = true;
+            return Some(Err(e));
+        }
+
+        Some(Ok((key, value)))
+    }
+}
+
+/// A logical partition that hash-partitions its keys over N partitions (shards)
+///
+/// Spreading writes over multiple partitions (and thus memtables) reduces
+/// contention for write-heavy workloads on many-core machines.
+/// Point operations are routed to a single shard, while iterators
+/// merge all shards, so they return items in key order.
+///
+/// Created using [`crate::Keyspace::open_sharded_partition`].
+///
+/// Keys are routed using a stable hash (CRC32), so the shard count needs to stay
+/// the same for the entire lifetime of the data. The shard count is stored
+/// in every shard, and reopening with a different shard count is rejected.
+#[derive(Clone)]
+pub struct ShardedPartition {
+    shards: Vec<PartitionHandle>,
+}
+
+impl ShardedPartition {
+    pub(crate) fn new(shards: Vec<PartitionHandle>) -> Self {
+        assert!(!shards.is_empty(), "should have at least one shard");
+        Self { shards }
+    }
+
+    /// Returns the underlying partitions.
+    #[must_use]
+    pub fn shards(&self) -> &[PartitionHandle] {
+        &self.shards
+    }
+
+    /// Returns the partition the given key is routed to.
+    #[must_use]
+    pub fn shard_for<K: AsRef<[u8]>>(&self, key: K) -> &PartitionHandle {
+        let hash = crc32fast::hash(key.as_ref()) as usize;
+
+        self.shards
+            .get(hash % self.shards.len())
+            .expect("should be in bounds")
+    }
+
+    /// Retrieves an item.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<Option<UserValue>> {
+        self.shard_for(&key).get(key)
+    }
+
+    /// Returns `true` if the partition contains the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<bool> {
+        self.shard_for(&key).contains_key(key)
+    }
+
+    /// Inserts a key-value pair.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
+    pub fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, key: K, value: V) -> crate::Result<()> {
+        self.shard_for(&key).insert(key, value)
+    }
+
+    /// Removes an item.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key is too large.
+    pub fn remove<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<()> {
+        self.shard_for(&key).remove(key)
+    }
+
+    /// Inserts a key-value pair into a write batch, allowing atomic writes across shards.
+    pub fn batch_insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        batch: &mut Batch,
+        key: K,
+        value: V,
+    ) {
+        batch.insert(self.shard_for(&key), key, value);
+    }
+
+    /// Adds a tombstone marker for a key into a write batch.
+    pub fn batch_remove<K: AsRef<[u8]>>(&self, batch: &mut Batch, key: K) {
+        batch.remove(self.shard_for(&key), key);
+    }
+
+    /// Returns an iterator that scans through all shards, in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let partition =
+    ///     keyspace.open_sharded_partition("events", 4, PartitionCreateOptions::default())?;
+    ///
+    /// for key in ["d", "a", "c", "b"] {
+    ///     partition.insert(key, "abc")?;
+    /// }
+    ///
+    /// let keys = partition
+    ///     .iter()
+    ///     .map(|item| item.map(|(key, _)| key.to_vec()))
+    ///     .collect::<fjall::Result<Vec<_>>>()?;
+    /// assert_eq!(keys, [b"a", b"b", b"c", b"d"]);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter(&self) -> impl Iterator<Item = crate::Result<KvPair>> + 'static {
+        MergeIter::new(
+            self.shards
+                .iter()
+                .map(|shard| Box::new(shard.iter()) as BoxedIter)
+                .collect(),
+        )
+    }
+
+    /// Returns an iterator over a range of items of all shards, in key order.
+    pub fn range<K: AsRef<[u8]>, R: RangeBounds<K> + Clone>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = crate::Result<KvPair>> + 'static {
+        MergeIter::new(
+            self.shards
+                .iter()
+                .map(|shard| Box::new(shard.range(range.clone())) as BoxedIter)
+                .collect(),
+        )
+    }
+
+    /// Returns an iterator over a prefixed set of items of all shards, in key order.
+    pub fn prefix<K: AsRef<[u8]>>(
+        &self,
+        prefix: K,
+    ) -> impl Iterator<Item = crate::Result<KvPair>> + 'static {
+        let prefix = prefix.as_ref();
+
+        MergeIter::new(
+            self.shards
+                .iter()
+                .map(|shard| Box::new(shard.prefix(prefix.to_vec())) as BoxedIter)
+                .collect(),
+        )
+    }
+
+    /// Approximates the amount of items over all shards.
+    #[must_use]
+    pub fn approximate_len(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(PartitionHandle::approximate_len)
+            .sum()
+    }
+
+    /// Scans all shards, returning the amount of items.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn len(&self) -> crate::Result<usize> {
+        self.shards
+            .iter()
+            .map(PartitionHandle::len)
+            .try_fold(0, |acc, len| Ok(acc + len?))
+    }
+
+    /// Returns `true` if all shards are empty.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn is_empty(&self) -> crate::Result<bool> {
+        for shard in &self.shards {
+            if !shard.is_empty()? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn merge_iter_fuses_after_error() {
+        let kv = |key: &str| -> crate::Result<KvPair> {
+            Ok((key.as_bytes().into(), key.as_bytes().into()))
+        };
+
+        let iters: Vec<BoxedIter> = vec![
+            Box::new(vec![kv("a"), kv("d")].into_iter()),
+            Box::new(vec![Err(crate::Error::PartitionDeleted)].into_iter()),
+            Box::new(vec![kv("b"), kv("c")].into_iter()),
+        ];
+
+        let mut iter = MergeIter::new(iters);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(crate::Error::PartitionDeleted))
+        ));
+        assert!(iter.next().is_none());
+    }
+}
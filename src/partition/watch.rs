@@ -0,0 +1,183 @@
+use lsm_tree::{SeqNo, UserKey, UserValue, ValueType};
+use std::{
+    ops::Deref,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex, Weak,
+    },
+};
+
+/// A change to a watched key
+///
+/// See [`crate::PartitionHandle::watch_prefix`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WatchEvent {
+    /// Key was inserted or updated
+    Insert {
+        /// Key
+        key: UserKey,
+
+        /// New value
+        value: UserValue,
+
+        /// Seqno of the write
+        seqno: SeqNo,
+    },
+
+    /// Key was removed
+    Remove {
+        /// Key
+        key: UserKey,
+
+        /// Seqno of the write
+        seqno: SeqNo,
+    },
+}
+
+impl WatchEvent {
+    pub(crate) fn new(key: UserKey, value: UserValue, value_type: ValueType, seqno: SeqNo) -> Self {
+        match value_type {
+            ValueType::Value => Self::Insert { key, value, seqno },
+            ValueType::Tombstone => Self::Remove { key, seqno },
+        }
+    }
+
+    /// Returns the key of the event.
+    #[must_use]
+    pub fn key(&self) -> &UserKey {
+        match self {
+            Self::Insert { key, .. } | Self::Remove { key, .. } => key,
+        }
+    }
+}
+
+/// Receives the events of a subscription
+///
+/// Created using [`crate::PartitionHandle::watch_prefix`].
+/// Dereferences to the underlying [`Receiver`].
+///
+/// Dropping it cancels the subscription.
+pub struct WatchReceiver {
+    rx: Receiver<WatchEvent>,
+
+    /// Keeps the subscription alive, see `Subscriber::token`
+    _token: Arc<()>,
+
+    /// Counts the live subscriptions, see `Watchers::live`
+    _live: Arc<()>,
+}
+
+impl Deref for WatchReceiver {
+    type Target = Receiver<WatchEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rx
+    }
+}
+
+struct Subscriber {
+    prefix: UserKey,
+    tx: Sender<WatchEvent>,
+
+    /// Dead once the receiver is dropped
+    ///
+    /// NOTE: A Sender cannot tell if its receiver was dropped without sending,
+    /// so subscribers of prefixes that are never written to would never be removed
+    token: Weak<()>,
+}
+
+/// Subscribers of a partition's write path
+#[derive(Default)]
+pub struct Watchers {
+    /// Shared with every receiver, so the strong count tells if anyone is watching
+    ///
+    /// Used as a fast path, so writes don't need to take the lock if nobody is watching.
+    live: Arc<()>,
+
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+impl Watchers {
+    pub fn subscribe(&self, prefix: UserKey) -> WatchReceiver {
+        let (tx, rx) = channel();
+        let token = Arc::new(());
+
+        let mut subscribers = self.subscribers.lock().expect("lock is poisoned");
+        subscribers.retain(|subscriber| subscriber.token.strong_count() > 0);
+        subscribers.push(Subscriber {
+            prefix,
+            tx,
+            token: Arc::downgrade(&token),
+        });
+
+        WatchReceiver {
+            rx,
+            _token: token,
+            _live: self.live.clone(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        Arc::strong_count(&self.live) > 1
+    }
+
+    /// Sends the events to all subscribers with a matching prefix.
+    ///
+    /// Subscribers whose receiver was dropped are removed.
+    pub fn notify(&self, events: &[WatchEvent]) {
+        if !self.is_active() {
+            return;
+        }
+
+        let mut subscribers = self.subscribers.lock().expect("lock is poisoned");
+
+        subscribers.retain(|subscriber| {
+            subscriber.token.strong_count() > 0
+                && events
+                    .iter()
+                    .filter(|event| event.key().starts_with(&subscriber.prefix))
+                    .all(|event| subscriber.tx.send(event.clone()).is_ok())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn watchers_prune_dropped_receivers() {
+        let watchers = Watchers::default();
+        assert!(!watchers.is_active());
+
+        let a = watchers.subscribe("a".as_bytes().into());
+        let b = watchers.subscribe("b".as_bytes().into());
+        assert!(watchers.is_active());
+
+        drop(a);
+        assert!(watchers.is_active());
+
+        // NOTE: No event matches the dropped subscriber, but it is removed anyway
+        watchers.notify(&[WatchEvent::new(
+            "b".as_bytes().into(),
+            "abc".as_bytes().into(),
+            ValueType::Value,
+            0,
+        )]);
+        assert_eq!(
+            1,
+            watchers.subscribers.lock().expect("lock is poisoned").len()
+        );
+        assert!(b.try_recv().is_ok());
+
+        drop(b);
+        assert!(!watchers.is_active());
+
+        let _c = watchers.subscribe("c".as_bytes().into());
+        assert_eq!(
+            1,
+            watchers.subscribers.lock().expect("lock is poisoned").len()
+        );
+    }
+}
@@ -2,15 +2,21 @@ use crate::{
     batch::PartitionKey,
     file::{
         FLUSH_MARKER, FLUSH_PARTITIONS_LIST, JOURNALS_FOLDER, PARTITIONS_FOLDER,
-        PARTITION_DELETED_MARKER,
+        PARTITION_DELETED_MARKER, REPLICATION_MARKER,
     },
-    journal::Journal,
-    partition::PartitionHandleInner,
+    flush::manager::FlushManager,
+    journal::{
+        shard::{corrupt_journal, RecoveryMode},
+        Journal,
+    },
+    keyspace::Partitions,
+    partition::{lock::LockManager, watch::Watchers, PartitionHandleInner},
     Keyspace, PartitionHandle,
 };
-use lsm_tree::MemTable;
+use lsm_tree::{MemTable, SeqNo};
 use std::{
     collections::HashMap,
+    path::Path,
     sync::{atomic::AtomicBool, Arc, RwLock},
 };
 
@@ -58,6 +64,10 @@ pub fn recover_partitions(
         let partition_inner = PartitionHandleInner {
             max_memtable_size: (8 * 1_024 * 1_024).into(),
             compaction_strategy: RwLock::new(Arc::new(lsm_tree::compaction::Levelled::default())),
+            write_interceptor: RwLock::default(),
+            locks: LockManager::default(),
+            watchers: Watchers::default(),
+            events: keyspace.events.clone(),
             name: partition_name.into(),
             tree,
             partitions: keyspace.partitions.clone(),
@@ -67,7 +77,7 @@ pub fn recover_partitions(
             journal_manager: keyspace.journal_manager.clone(),
             journal: keyspace.journal.clone(),
             compaction_manager: keyspace.compaction_manager.clone(),
-            seqno: keyspace.seqno.clone(),
+            commit_pipeline: keyspace.commit_pipeline.clone(),
             write_buffer_manager: keyspace.write_buffer_manager.clone(),
             is_deleted: AtomicBool::default(),
             is_poisoned: keyspace.is_poisoned.clone(),
@@ -93,7 +103,13 @@ pub fn recover_partitions(
         }
 
         // Recover seqno
-        let maybe_next_seqno = partition.tree.get_lsn().map(|x| x + 1).unwrap_or_default();
+        //
+        // NOTE: The seqno of the active memtable was already taken from the journal
+        let maybe_next_seqno = partition
+            .tree
+            .get_segment_lsn()
+            .map(|x| x + 1)
+            .unwrap_or_default();
         keyspace
             .seqno
             .fetch_max(maybe_next_seqno, std::sync::atomic::Ordering::AcqRel);
@@ -113,6 +129,20 @@ pub fn recover_partitions(
     Ok(())
 }
 
+/// Removes temporary files of operations that were interrupted by a crash
+///
+/// Only temporary files that fjall itself writes are removed.
+pub fn remove_orphans<P: AsRef<Path>>(path: P) -> crate::Result<()> {
+    let path = path.as_ref().join(format!("{REPLICATION_MARKER}.tmp"));
+
+    if path.try_exists()? {
+        log::info!("Deleting orphaned temporary file {path:?}");
+        std::fs::remove_file(&path)?;
+    }
+
+    Ok(())
+}
+
 pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
     use crate::journal::partition_manifest::{
         Error as PartitionManifestParseError, PartitionManifest,
@@ -124,7 +154,20 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
 
     let journals_folder = keyspace.config.path.join(JOURNALS_FOLDER);
     let mut dirents = std::fs::read_dir(journals_folder)?.collect::<std::io::Result<Vec<_>>>()?;
-    dirents.sort_by_key(std::fs::DirEntry::file_name);
+
+    // IMPORTANT: Sort numerically, journals need to be recovered from oldest to newest
+    dirents.sort_by_key(|dirent| {
+        dirent
+            .file_name()
+            .to_str()
+            .expect("should be utf-8")
+            .parse::<lsm_tree::SegmentId>()
+            .expect("should be valid journal ID")
+    });
+
+    // NOTE: Used to merge the data of all sealed journals, see Config::merge_recovered_journals
+    let mut merged_memtables = HashMap::new();
+    let mut merged_lsns = HashMap::new();
 
     for dirent in dirents {
         let journal_path = dirent.path();
@@ -142,14 +185,18 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
             // Only consider partitions that are registered in the journal
             let file_content = std::fs::read_to_string(journal_path.join(FLUSH_PARTITIONS_LIST))?;
             let partitions_to_consider = match PartitionManifest::from_str(&file_content) {
-                Ok(v) => Ok(v),
-                Err(e) => match e {
-                    PartitionManifestParseError::Io(e) => Err(crate::Error::from(e)),
-                    e => {
-                        panic!("invalid partition reference file: {e:?}")
+                Ok(v) => v,
+                Err(PartitionManifestParseError::Io(e)) => return Err(crate::Error::from(e)),
+                Err(e) => {
+                    if keyspace.config.journal_recovery_mode == RecoveryMode::SkipInvalidBatches {
+                        log::error!("Skipping sealed journal {journal_path:?}, invalid partition reference file: {e:?}");
+                        continue;
                     }
-                },
-            }?;
+
+                    log::error!("Invalid partition reference file in {journal_path:?}: {e:?}");
+                    return Err(corrupt_journal("invalid partition reference file"));
+                }
+            };
 
             log::trace!(
                 "Journal contains data of {} partitions",
@@ -187,17 +234,44 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
                 }
             }
 
+            // NOTE: If we crashed after the last flush of this journal, but before it was
+            // garbage collected, no partition has any data left in it
+            if partition_seqno_map.is_empty() {
+                log::info!("Deleting fully flushed journal {journal_path:?}");
+                std::fs::remove_dir_all(&journal_path)?;
+                continue;
+            }
+
             // Recover sealed memtables for affected partitions
             let partition_names_to_recover =
                 partition_seqno_map.keys().cloned().collect::<Vec<_>>();
 
             log::trace!("Recovering memtables for partitions: {partition_names_to_recover:#?}");
-            let memtables = Journal::recover_memtables(
-                &journal_path,
-                Some(&partition_names_to_recover),
-                keyspace.config.journal_recovery_mode,
-            )?;
-            log::trace!("Recovered {} sealed memtables", memtables.len());
+
+            if keyspace.config.merge_recovered_journals {
+                Journal::recover_memtables_into(
+                    &journal_path,
+                    Some(&partition_names_to_recover),
+                    keyspace.config.journal_recovery_mode,
+                    &mut merged_memtables,
+                    &mut merged_lsns,
+                )?;
+            } else {
+                let (memtables, lsns) = Journal::recover_memtables(
+                    &journal_path,
+                    Some(&partition_names_to_recover),
+                    keyspace.config.journal_recovery_mode,
+                )?;
+                log::trace!("Recovered {} sealed memtables", memtables.len());
+
+                enqueue_sealed_memtables(
+                    keyspace,
+                    &partitions_lock,
+                    &mut flush_manager_lock,
+                    memtables,
+                    &lsns,
+                );
+            }
 
             // IMPORTANT: Add sealed journal to journal manager
             journal_manager_lock.enqueue(crate::journal::manager::Item {
@@ -206,49 +280,72 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
                 size_in_bytes: journal_size,
             });
 
-            // Consume memtables by giving back to the partition
-            for (partition_name, sealed_memtable) in memtables {
-                let Some(partition) = partitions_lock.get(&partition_name) else {
-                    // Should not happen
-                    continue;
-                };
+            log::trace!("Requeued sealed journal at {:?}", journal_path);
+        }
+    }
 
-                let memtable_id = partition.tree.get_next_segment_id();
-                let sealed_memtable = Arc::new(sealed_memtable);
+    if !merged_memtables.is_empty() {
+        log::debug!(
+            "Recovered {} merged sealed memtables",
+            merged_memtables.len()
+        );
+
+        enqueue_sealed_memtables(
+            keyspace,
+            &partitions_lock,
+            &mut flush_manager_lock,
+            merged_memtables,
+            &merged_lsns,
+        );
+    }
 
-                partition
-                    .tree
-                    .add_sealed_memtable(memtable_id, sealed_memtable.clone());
+    Ok(())
+}
 
-                // Maybe the memtable has a higher seqno, so try to set to maximum
-                let maybe_next_seqno = partition.tree.get_lsn().map(|x| x + 1).unwrap_or_default();
-                keyspace
-                    .seqno
-                    .fetch_max(maybe_next_seqno, std::sync::atomic::Ordering::AcqRel);
+/// Gives recovered sealed memtables back to their partitions, and queues them for flushing
+fn enqueue_sealed_memtables(
+    keyspace: &Keyspace,
+    partitions: &Partitions,
+    flush_manager: &mut FlushManager,
+    memtables: HashMap<PartitionKey, MemTable>,
+    lsns: &HashMap<PartitionKey, SeqNo>,
+) {
+    for (partition_name, sealed_memtable) in memtables {
+        let Some(partition) = partitions.get(&partition_name) else {
+            // Should not happen
+            continue;
+        };
 
-                log::debug!("Keyspace seqno is now {}", keyspace.seqno.get());
+        let memtable_id = partition.tree.get_next_segment_id();
+        let sealed_memtable = Arc::new(sealed_memtable);
 
-                // IMPORTANT: Add sealed memtable size to current write buffer size
-                keyspace
-                    .write_buffer_manager
-                    .allocate(sealed_memtable.size().into());
+        partition
+            .tree
+            .add_sealed_memtable(memtable_id, sealed_memtable.clone());
 
-                // TODO: unit test write buffer size after recovery
+        // Maybe the memtable has a higher seqno, so try to set to maximum
+        let maybe_next_seqno = lsns.get(&partition_name).map(|x| x + 1).unwrap_or_default();
+        keyspace
+            .seqno
+            .fetch_max(maybe_next_seqno, std::sync::atomic::Ordering::AcqRel);
 
-                // IMPORTANT: Add sealed memtable to flush manager, so it can be flushed
-                flush_manager_lock.enqueue_task(
-                    partition_name,
-                    crate::flush::manager::Task {
-                        id: memtable_id,
-                        sealed_memtable,
-                        partition: partition.clone(),
-                    },
-                );
-            }
+        log::debug!("Keyspace seqno is now {}", keyspace.seqno.get());
 
-            log::trace!("Requeued sealed journal at {:?}", journal_path);
-        }
+        // IMPORTANT: Add sealed memtable size to current write buffer size
+        keyspace
+            .write_buffer_manager
+            .allocate(sealed_memtable.size().into());
+
+        // TODO: unit test write buffer size after recovery
+
+        // IMPORTANT: Add sealed memtable to flush manager, so it can be flushed
+        flush_manager.enqueue_task(
+            partition_name,
+            crate::flush::manager::Task {
+                id: memtable_id,
+                sealed_memtable,
+                partition: partition.clone(),
+            },
+        );
     }
-
-    Ok(())
 }
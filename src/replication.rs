@@ -0,0 +1,184 @@
+use crate::{
+    batch::Batch,
+    file::{rename_atomic, JOURNALS_FOLDER, REPLICATION_MARKER},
+    journal::{frame::decode_frame, Journal},
+    Keyspace, PersistMode,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use lsm_tree::SeqNo;
+use std::{fs::File, io::Write, path::Path};
+
+/// Loads the seqno of the last applied replicated batch
+pub fn load_replicated_seqno<P: AsRef<Path>>(path: P) -> crate::Result<Option<SeqNo>> {
+    let path = path.as_ref().join(REPLICATION_MARKER);
+
+    if !path.try_exists()? {
+        return Ok(None);
+    }
+
+    let mut file = File::open(path)?;
+    Ok(Some(file.read_u64::<BigEndian>()?))
+}
+
+/// Durably stores the seqno of the last applied replicated batch
+fn store_replicated_seqno<P: AsRef<Path>>(path: P, seqno: SeqNo) -> crate::Result<()> {
+    let path = path.as_ref();
+    let tmp_path = path.join(format!("{REPLICATION_MARKER}.tmp"));
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_u64::<BigEndian>(seqno)?;
+    file.flush()?;
+    file.sync_all()?;
+
+    // IMPORTANT: Rename, so the marker is replaced atomically
+    rename_atomic(&tmp_path, path.join(REPLICATION_MARKER))?;
+
+    Ok(())
+}
+
+/// A committed write batch, read from the journal, that can be shipped to a replica
+///
+/// See [`Keyspace::journal_tail`] and [`Keyspace::apply_replicated_batch`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplicatedBatch {
+    /// Seqno the batch was committed with
+    pub seqno: SeqNo,
+
+    /// The batch in its journal format, including its checksum
+    pub bytes: Vec<u8>,
+}
+
+impl Keyspace {
+    /// Returns all batches with a seqno >= `from_seqno` that are still journaled, ordered by seqno.
+    ///
+    /// Journals are deleted once all their data has been flushed, so a replica that lags behind
+    /// too far may not be able to catch up. Check the seqno of the first returned batch to detect that.
+    ///
+    /// Writes are blocked while the journals are read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let items = keyspace.open_partition("items", PartitionCreateOptions::default())?;
+    /// items.insert("a", "abc")?;
+    /// items.insert("b", "def")?;
+    ///
+    /// let batches = keyspace.journal_tail(0)?;
+    /// assert_eq!(2, batches.len());
+    ///
+    /// let batches = keyspace.journal_tail(batches[0].seqno + 1)?;
+    /// assert_eq!(1, batches.len());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn journal_tail(&self, from_seqno: SeqNo) -> crate::Result<Vec<ReplicatedBatch>> {
+        // IMPORTANT: Lock all journal shards, so no batch is in flight while reading
+        // Otherwise, a batch could become visible after one with a higher seqno was already shipped
+        let mut journal_lock = self.journal.full_lock();
+
+        for shard in &mut journal_lock {
+            shard.writer.flush(PersistMode::Buffer)?;
+        }
+
+        // NOTE: Prevents sealed journals from being deleted while reading them
+        let _journal_manager = self.journal_manager.read().expect("lock is poisoned");
+
+        let mut batches = vec![];
+
+        for dirent in std::fs::read_dir(self.config.path.join(JOURNALS_FOLDER))? {
+            let dirent = dirent?;
+
+            for (seqno, bytes) in Journal::read_frames(dirent.path(), from_seqno)? {
+                batches.push(ReplicatedBatch { seqno, bytes });
+            }
+        }
+
+        batches.sort_by_key(|batch| batch.seqno);
+
+        Ok(batches)
+    }
+
+    /// Returns the seqno of the last batch that was applied using [`Keyspace::apply_replicated_batch`].
+    ///
+    /// The value survives restarts, so a replica can resume tailing from it.
+    #[must_use]
+    pub fn replicated_seqno(&self) -> Option<SeqNo> {
+        *self.replicated_seqno.lock().expect("lock is poisoned")
+    }
+
+    /// Applies a batch that was shipped from another keyspace using [`Keyspace::journal_tail`].
+    ///
+    /// The batch keeps its original seqno, instead of getting a new one from this keyspace.
+    /// Items of partitions that do not exist in this keyspace are skipped, so partitions
+    /// need to be opened before applying batches.
+    ///
+    /// Batches need to be applied in seqno order. The seqno of the last applied batch is
+    /// stored durably, and batches that are not newer than it are ignored, so retrying
+    /// after a crash never applies a batch twice.
+    ///
+    /// Returns the seqno of the batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let primary = Config::new(folder).open()?;
+    /// # let folder = tempfile::tempdir()?;
+    /// # let replica = Config::new(folder).open()?;
+    /// let items = primary.open_partition("items", PartitionCreateOptions::default())?;
+    /// items.insert("a", "abc")?;
+    ///
+    /// let replicated_items = replica.open_partition("items", PartitionCreateOptions::default())?;
+    ///
+    /// for batch in primary.journal_tail(0)? {
+    ///     replica.apply_replicated_batch(&batch.bytes)?;
+    /// }
+    ///
+    /// assert!(replicated_items.contains_key("a")?);
+    /// assert_eq!(Some(0), replica.replicated_seqno());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the batch is malformed or its checksum does not match.
+    pub fn apply_replicated_batch(&self, bytes: &[u8]) -> crate::Result<SeqNo> {
+        let (seqno, items) = decode_frame(bytes)?;
+
+        let mut replicated_seqno = self.replicated_seqno.lock().expect("lock is poisoned");
+
+        if let Some(replicated_seqno) = *replicated_seqno {
+            if seqno <= replicated_seqno {
+                log::debug!("Batch {seqno} was already applied, skipping");
+                return Ok(seqno);
+            }
+        }
+
+        let mut batch = Batch::with_capacity(self.clone(), items.len());
+        batch.data = items;
+        batch.seqno = Some(seqno);
+        batch.commit()?;
+
+        // IMPORTANT: The batch needs to be durable before the marker moves past it
+        //
+        // If we crash in between, the batch is applied again, which is fine, because it
+        // is written with the same seqno, so it is the exact same version
+        self.persist(PersistMode::SyncAll)?;
+        store_replicated_seqno(&self.config.path, seqno)?;
+
+        *replicated_seqno = Some(seqno);
+
+        Ok(seqno)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::batch::PartitionKey;
+use lsm_tree::UserKey;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// An operation that took longer than the configured threshold
+///
+/// See [`crate::Config::slow_op_threshold`].
+#[derive(Clone, Debug)]
+pub enum SlowOp {
+    /// Point read
+    Get {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Key that was read
+        key: UserKey,
+
+        /// Time spent
+        duration: Duration,
+    },
+
+    /// Full, range or prefix scan
+    ///
+    /// Reported when the iterator is dropped.
+    Range {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Amount of items that were read
+        item_count: usize,
+
+        /// Time spent inside the iterator, not including the caller's time between items
+        duration: Duration,
+    },
+
+    /// Memtable flush
+    Flush {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Size of the flushed memtable in bytes
+        memtable_size: u32,
+
+        /// Time spent
+        duration: Duration,
+    },
+
+    /// Compaction
+    Compaction {
+        /// Partition name
+        partition: PartitionKey,
+
+        /// Time spent
+        duration: Duration,
+    },
+}
+
+type SlowOpCallback = Arc<dyn Fn(&SlowOp) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct SlowOpObserver {
+    threshold: Duration,
+    callback: SlowOpCallback,
+}
+
+impl SlowOpObserver {
+    pub fn new(threshold: Duration, callback: SlowOpCallback) -> Self {
+        Self {
+            threshold,
+            callback,
+        }
+    }
+
+    /// Invokes the callback if the operation took too long.
+    pub fn observe<F: FnOnce(Duration) -> SlowOp>(&self, duration: Duration, f: F) {
+        if duration >= self.threshold {
+            (self.callback)(&f(duration));
+        }
+    }
+
+    /// Runs an operation, and invokes the callback if it took too long.
+    pub fn time<T, F: FnOnce() -> T, D: FnOnce(Duration) -> SlowOp>(
+        &self,
+        op: F,
+        describe: D,
+    ) -> T {
+        let start = Instant::now();
+        let result = op();
+        self.observe(start.elapsed(), describe);
+        result
+    }
+}
+
+/// Measures the time spent inside a scan, and reports it once the scan is dropped
+pub struct ObservedScan<I> {
+    inner: I,
+    observer: Option<(SlowOpObserver, PartitionKey)>,
+    item_count: usize,
+    duration: Duration,
+}
+
+impl<I> ObservedScan<I> {
+    pub fn new(inner: I, observer: Option<(SlowOpObserver, PartitionKey)>) -> Self {
+        Self {
+            inner,
+            observer,
+            item_count: 0,
+            duration: Duration::ZERO,
+        }
+    }
+
+    fn measure<T, F: FnOnce(&mut I) -> Option<T>>(&mut self, f: F) -> Option<T> {
+        if self.observer.is_none() {
+            return f(&mut self.inner);
+        }
+
+        let start = Instant::now();
+        let item = f(&mut self.inner);
+        self.duration += start.elapsed();
+
+        if item.is_some() {
+            self.item_count += 1;
+        }
+
+        item
+    }
+}
+
+impl<I: Iterator> Iterator for ObservedScan<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.measure(Iterator::next)
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for ObservedScan<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.measure(DoubleEndedIterator::next_back)
+    }
+}
+
+impl<I> Drop for ObservedScan<I> {
+    fn drop(&mut self) {
+        if let Some((observer, partition)) = self.observer.take() {
+            observer.observe(self.duration, |duration| SlowOp::Range {
+                partition,
+                item_count: self.item_count,
+                duration,
+            });
+        }
+    }
+}
@@ -124,6 +124,21 @@ impl TxKeyspace {
         self.inner.journal_count()
     }
 
+    /// Runs one round of maintenance in the calling thread.
+    ///
+    /// See [`Keyspace::maintenance`].
+    pub fn maintenance(&self) {
+        self.inner.maintenance();
+    }
+
+    /// Subscribes to background events.
+    ///
+    /// See [`Keyspace::events`].
+    #[must_use]
+    pub fn events(&self) -> std::sync::mpsc::Receiver<crate::Event> {
+        self.inner.events()
+    }
+
     /// Returns the disk space usage of the entire keyspace.
     #[must_use]
     pub fn disk_space(&self) -> u64 {
@@ -137,7 +152,10 @@ impl TxKeyspace {
     /// Returns error, if an IO error occured.
     pub fn open(config: Config) -> crate::Result<Self> {
         let inner = Keyspace::create_or_recover(config)?;
-        inner.start_background_threads();
+
+        if inner.config.background_threads {
+            inner.start_background_threads();
+        }
 
         Ok(Self {
             inner,
@@ -1,5 +1,5 @@
 use crate::PartitionHandle;
-use lsm_tree::UserValue;
+use lsm_tree::{SeqNo, UserValue};
 use std::{
     path::PathBuf,
     sync::{Arc, Mutex},
@@ -182,7 +182,7 @@ impl TransactionalPartitionHandle {
 
     /// Inserts a key-value pair into the partition.
     ///
-    /// Keys may be up to 65536 bytes long, values up to 65536 bytes.
+    /// Keys may be up to 65535 bytes long, values up to 65535 bytes.
     /// Shorter keys and values result in better performance.
     ///
     /// If the key already exists, the item will be overwritten.
@@ -206,23 +206,31 @@ impl TransactionalPartitionHandle {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
     pub fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, key: K, value: V) -> crate::Result<()> {
-        let value = value.as_ref();
-
-        // TODO: remove in 2.0.0
-        assert!(
-            u16::try_from(value.len()).is_ok(),
-            "Value should be 65535 bytes or less"
-        );
+        self.insert_with_seqno(key, value)?;
+        Ok(())
+    }
 
+    /// Inserts a key-value pair into the partition, like [`TransactionalPartitionHandle::insert`].
+    ///
+    /// Returns the seqno that was assigned to the write.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if the key or value is too large.
+    pub fn insert_with_seqno<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> crate::Result<SeqNo> {
         let _lock = self.tx_lock.lock().expect("lock is poisoned");
-        self.inner.insert(key, value)
+        self.inner.insert_with_seqno(key, value)
     }
 
     /// Removes an item from the partition.
     ///
-    /// The key may be up to 65536 bytes long.
+    /// The key may be up to 65535 bytes long.
     /// Shorter keys result in better performance.
     ///
     /// The operation will run wrapped in a transaction.
@@ -248,8 +256,20 @@ impl TransactionalPartitionHandle {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn remove<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<()> {
+        self.remove_with_seqno(key)?;
+        Ok(())
+    }
+
+    /// Removes an item from the partition, like [`TransactionalPartitionHandle::remove`].
+    ///
+    /// Returns the seqno that was assigned to the write.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn remove_with_seqno<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<SeqNo> {
         let _lock = self.tx_lock.lock().expect("lock is poisoned");
-        self.inner.remove(key)
+        self.inner.remove_with_seqno(key)
     }
 
     /// Retrieves an item from the partition.
@@ -357,7 +357,7 @@ impl<'a> WriteTransaction<'a> {
 
     /// Inserts a key-value pair into the partition.
     ///
-    /// Keys may be up to 65536 bytes long, values up to 65536 bytes.
+    /// Keys may be up to 65535 bytes long, values up to 65535 bytes.
     /// Shorter keys and values result in better performance.
     ///
     /// If the key already exists, the item will be overwritten.
@@ -393,20 +393,12 @@ impl<'a> WriteTransaction<'a> {
         key: K,
         value: V,
     ) {
-        let value = value.as_ref();
-
-        // TODO: remove in 2.0.0
-        assert!(
-            u16::try_from(value.len()).is_ok(),
-            "Value should be 65535 bytes or less"
-        );
-
         self.memtables
             .entry(partition.inner.name.clone())
             .or_default()
             .insert(lsm_tree::Value::new(
                 key.as_ref(),
-                value,
+                value.as_ref(),
                 // NOTE: Just take the max seqno, which should never be reached
                 // that way, the write is definitely always the newest
                 SeqNo::MAX,
@@ -416,7 +408,7 @@ impl<'a> WriteTransaction<'a> {
 
     /// Removes an item from the partition.
     ///
-    /// The key may be up to 65536 bytes long.
+    /// The key may be up to 65535 bytes long.
     /// Shorter keys result in better performance.
     ///
     /// # Examples
@@ -464,7 +456,7 @@ impl<'a> WriteTransaction<'a> {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
+    /// Will return `Err` if an IO error occurs, or if any key or value is too large.
     pub fn commit(self) -> crate::Result<()> {
         let mut batch = Batch::with_capacity(self.keyspace, 10);
 
@@ -481,7 +473,9 @@ impl<'a> WriteTransaction<'a> {
 
         // TODO: instead of using batch, write batch::commit as a generic function that takes
         // a impl Iterator<BatchItem>
-        batch.commit()
+        batch.commit()?;
+
+        Ok(())
     }
 
     /// More explicit alternative to dropping the transaction
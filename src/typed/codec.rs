@@ -0,0 +1,102 @@
+use crate::Error;
+
+/// Codec failures are reported as [`std::io::ErrorKind::InvalidData`].
+// TODO: dedicated error variant in 2.0.0
+fn codec_error<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Converts typed keys or values to bytes and back
+///
+/// Keys are compared by their encoded bytes, so a key codec should be
+/// order-preserving if range scans over the typed keys are needed.
+pub trait Codec {
+    /// The decoded type
+    type Item;
+
+    /// Encodes an item.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the item cannot be encoded.
+    fn encode(item: &Self::Item) -> crate::Result<Vec<u8>>;
+
+    /// Decodes an item.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the bytes are not a valid encoding.
+    fn decode(bytes: &[u8]) -> crate::Result<Self::Item>;
+}
+
+/// Stores byte vectors as-is
+///
+/// Order-preserving.
+pub struct Bytes;
+
+impl Codec for Bytes {
+    type Item = Vec<u8>;
+
+    fn encode(item: &Self::Item) -> crate::Result<Vec<u8>> {
+        Ok(item.clone())
+    }
+
+    fn decode(bytes: &[u8]) -> crate::Result<Self::Item> {
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Stores strings as UTF-8
+///
+/// Order-preserving.
+pub struct Utf8;
+
+impl Codec for Utf8 {
+    type Item = String;
+
+    fn encode(item: &Self::Item) -> crate::Result<Vec<u8>> {
+        Ok(item.as_bytes().to_vec())
+    }
+
+    fn decode(bytes: &[u8]) -> crate::Result<Self::Item> {
+        String::from_utf8(bytes.to_vec()).map_err(codec_error)
+    }
+}
+
+/// Stores integers as big-endian
+///
+/// Order-preserving.
+pub struct BigEndianU64;
+
+impl Codec for BigEndianU64 {
+    type Item = u64;
+
+    fn encode(item: &Self::Item) -> crate::Result<Vec<u8>> {
+        Ok(crate::key::encode_u64(*item).to_vec())
+    }
+
+    fn decode(bytes: &[u8]) -> crate::Result<Self::Item> {
+        crate::key::decode_u64(bytes)
+            .ok_or_else(|| codec_error(format!("expected 8 bytes, got {}", bytes.len())))
+    }
+}
+
+/// Stores any serde type using bincode
+///
+/// **Not** order-preserving (integers are little-endian), so it should
+/// generally only be used for values.
+#[cfg(feature = "bincode")]
+pub struct Bincode<T>(std::marker::PhantomData<T>);
+
+#[cfg(feature = "bincode")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec for Bincode<T> {
+    type Item = T;
+
+    fn encode(item: &Self::Item) -> crate::Result<Vec<u8>> {
+        bincode::serialize(item).map_err(codec_error)
+    }
+
+    fn decode(bytes: &[u8]) -> crate::Result<Self::Item> {
+        bincode::deserialize(bytes).map_err(codec_error)
+    }
+}
@@ -0,0 +1,380 @@
+//! Typed access to partitions.
+//!
+//! A [`TypedPartition`] wraps a [`PartitionHandle`] and converts keys and values
+//! using a [`Codec`], so applications don't need to hand-roll (de)serialization
+//! around every call.
+//!
+//! # Examples
+//!
+//! ```
+//! # use fjall::{Config, PartitionCreateOptions};
+//! use fjall::typed::{codec::{BigEndianU64, Utf8}, TypedPartition};
+//! #
+//! # let folder = tempfile::tempdir()?;
+//! # let keyspace = Config::new(folder).open()?;
+//! let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+//! let users = TypedPartition::<BigEndianU64, Utf8>::new(partition);
+//!
+//! users.insert(&1, &"alice".to_string())?;
+//! users.insert(&2, &"bob".to_string())?;
+//!
+//! assert_eq!(Some("alice".to_string()), users.get(&1)?);
+//! assert_eq!(1, users.range(2..)?.count());
+//! #
+//! # Ok::<(), fjall::Error>(())
+//! ```
+
+pub mod codec;
+
+use crate::PartitionHandle;
+use codec::Codec;
+use lsm_tree::KvPair;
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+#[cfg(feature = "single_writer_tx")]
+use crate::TxPartitionHandle;
+
+type TypedKvPair<K, V> = (<K as Codec>::Item, <V as Codec>::Item);
+
+fn decode_kv<K: Codec, V: Codec>((k, v): KvPair) -> crate::Result<TypedKvPair<K, V>> {
+    Ok((K::decode(&k)?, V::decode(&v)?))
+}
+
+fn encode_bound<K: Codec>(bound: Bound<&K::Item>) -> crate::Result<Bound<Vec<u8>>> {
+    Ok(match bound {
+        Bound::Included(key) => Bound::Included(K::encode(key)?),
+        Bound::Excluded(key) => Bound::Excluded(K::encode(key)?),
+        Bound::Unbounded => Bound::Unbounded,
+    })
+}
+
+/// A partition with typed keys and values
+///
+/// Keys are encoded using `K`, values using `V`.
+pub struct TypedPartition<K: Codec, V: Codec> {
+    inner: PartitionHandle,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K: Codec, V: Codec> Clone for TypedPartition<K, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<K: Codec + 'static, V: Codec + 'static> TypedPartition<K, V> {
+    /// Wraps a partition.
+    #[must_use]
+    pub fn new(partition: PartitionHandle) -> Self {
+        Self {
+            inner: partition,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the underlying partition.
+    #[must_use]
+    pub fn inner(&self) -> &PartitionHandle {
+        &self.inner
+    }
+
+    /// Inserts a key-value pair into the partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be encoded.
+    pub fn insert(&self, key: &K::Item, value: &V::Item) -> crate::Result<()> {
+        self.inner.insert(K::encode(key)?, V::encode(value)?)
+    }
+
+    /// Removes an item from the partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key cannot be encoded.
+    pub fn remove(&self, key: &K::Item) -> crate::Result<()> {
+        self.inner.remove(K::encode(key)?)
+    }
+
+    /// Retrieves an item from the partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be (de)coded.
+    pub fn get(&self, key: &K::Item) -> crate::Result<Option<V::Item>> {
+        self.inner
+            .get(K::encode(key)?)?
+            .map(|value| V::decode(&value))
+            .transpose()
+    }
+
+    /// Returns `true` if the partition contains the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key cannot be encoded.
+    pub fn contains_key(&self, key: &K::Item) -> crate::Result<bool> {
+        self.inner.contains_key(K::encode(key)?)
+    }
+
+    /// Returns an iterator that scans through the entire partition.
+    #[must_use]
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<TypedKvPair<K, V>>> + 'static {
+        self.inner
+            .iter()
+            .map(|item| item.and_then(decode_kv::<K, V>))
+    }
+
+    /// Returns an iterator over a range of items.
+    ///
+    /// The range is based on the *encoded* keys, so `K` should be order-preserving.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the range bounds cannot be encoded.
+    pub fn range<R: RangeBounds<K::Item>>(
+        &self,
+        range: R,
+    ) -> crate::Result<impl DoubleEndedIterator<Item = crate::Result<TypedKvPair<K, V>>> + 'static>
+    {
+        let range = (
+            encode_bound::<K>(range.start_bound())?,
+            encode_bound::<K>(range.end_bound())?,
+        );
+
+        Ok(self
+            .inner
+            .range(range)
+            .map(|item| item.and_then(decode_kv::<K, V>)))
+    }
+
+    /// Returns the first key-value pair in the partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the item cannot be decoded.
+    pub fn first_key_value(&self) -> crate::Result<Option<TypedKvPair<K, V>>> {
+        self.iter().next().transpose()
+    }
+
+    /// Returns the last key-value pair in the partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the item cannot be decoded.
+    pub fn last_key_value(&self) -> crate::Result<Option<TypedKvPair<K, V>>> {
+        self.iter().next_back().transpose()
+    }
+}
+
+/// A transactional partition with typed keys and values
+///
+/// Keys are encoded using `K`, values using `V`.
+#[cfg(feature = "single_writer_tx")]
+pub struct TypedTxPartition<K: Codec, V: Codec> {
+    inner: TxPartitionHandle,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+#[cfg(feature = "single_writer_tx")]
+impl<K: Codec, V: Codec> Clone for TypedTxPartition<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "single_writer_tx")]
+impl<K: Codec + 'static, V: Codec + 'static> TypedTxPartition<K, V> {
+    /// Wraps a transactional partition.
+    #[must_use]
+    pub fn new(partition: TxPartitionHandle) -> Self {
+        Self {
+            inner: partition,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the underlying partition.
+    #[must_use]
+    pub fn inner(&self) -> &TxPartitionHandle {
+        &self.inner
+    }
+
+    /// Inserts a key-value pair into the partition.
+    ///
+    /// The operation will run wrapped in a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be encoded.
+    pub fn insert(&self, key: &K::Item, value: &V::Item) -> crate::Result<()> {
+        self.inner.insert(K::encode(key)?, V::encode(value)?)
+    }
+
+    /// Removes an item from the partition.
+    ///
+    /// The operation will run wrapped in a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key cannot be encoded.
+    pub fn remove(&self, key: &K::Item) -> crate::Result<()> {
+        self.inner.remove(K::encode(key)?)
+    }
+
+    /// Retrieves an item from the partition.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be (de)coded.
+    pub fn get(&self, key: &K::Item) -> crate::Result<Option<V::Item>> {
+        self.inner
+            .get(K::encode(key)?)?
+            .map(|value| V::decode(&value))
+            .transpose()
+    }
+
+    /// Returns `true` if the partition contains the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key cannot be encoded.
+    pub fn contains_key(&self, key: &K::Item) -> crate::Result<bool> {
+        self.inner.contains_key(K::encode(key)?)
+    }
+
+    /// Atomically updates an item and returns the previous value.
+    ///
+    /// Returning `None` removes the item if it existed before.
+    ///
+    /// The operation will run wrapped in a transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// use fjall::typed::{codec::{BigEndianU64, Utf8}, TypedTxPartition};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let counters = TypedTxPartition::<Utf8, BigEndianU64>::new(partition);
+    /// counters.insert(&"hits".to_string(), &1)?;
+    ///
+    /// let prev = counters.fetch_update(&"hits".to_string(), |n| n.map(|n| n + 1))?;
+    /// assert_eq!(Some(1), prev);
+    /// assert_eq!(Some(2), counters.get(&"hits".to_string())?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be (de)coded.
+    pub fn fetch_update<F: Fn(Option<&V::Item>) -> Option<V::Item>>(
+        &self,
+        key: &K::Item,
+        f: F,
+    ) -> crate::Result<Option<V::Item>> {
+        self.update(key, f).map(|(prev, _)| prev)
+    }
+
+    /// Atomically updates an item and returns the new value.
+    ///
+    /// Returning `None` removes the item if it existed before.
+    ///
+    /// The operation will run wrapped in a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be (de)coded.
+    pub fn update_fetch<F: Fn(Option<&V::Item>) -> Option<V::Item>>(
+        &self,
+        key: &K::Item,
+        f: F,
+    ) -> crate::Result<Option<V::Item>> {
+        self.update(key, f).map(|(_, updated)| updated)
+    }
+
+    /// Atomically updates an item, returning the previous and the new value.
+    fn update<F: Fn(Option<&V::Item>) -> Option<V::Item>>(
+        &self,
+        key: &K::Item,
+        f: F,
+    ) -> crate::Result<(Option<V::Item>, Option<V::Item>)> {
+        let _lock = self.inner.tx_lock.lock().expect("lock is poisoned");
+
+        let key = K::encode(key)?;
+
+        let prev = self
+            .inner
+            .inner
+            .get(&key)?
+            .map(|value| V::decode(&value))
+            .transpose()?;
+
+        let updated = f(prev.as_ref());
+
+        if let Some(value) = &updated {
+            self.inner.inner.insert(&key, V::encode(value)?)?;
+        } else if prev.is_some() {
+            self.inner.inner.remove(&key)?;
+        }
+
+        Ok((prev, updated))
+    }
+
+    /// Removes an item and returns its value if it existed.
+    ///
+    /// The operation will run wrapped in a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the key or value cannot be (de)coded.
+    pub fn take(&self, key: &K::Item) -> crate::Result<Option<V::Item>> {
+        self.fetch_update(key, |_| None)
+    }
+
+    /// Returns an iterator that scans through the entire partition.
+    #[must_use]
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<TypedKvPair<K, V>>> + 'static {
+        self.inner
+            .inner
+            .iter()
+            .map(|item| item.and_then(decode_kv::<K, V>))
+    }
+
+    /// Returns an iterator over a range of items.
+    ///
+    /// The range is based on the *encoded* keys, so `K` should be order-preserving.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the range bounds cannot be encoded.
+    pub fn range<R: RangeBounds<K::Item>>(
+        &self,
+        range: R,
+    ) -> crate::Result<impl DoubleEndedIterator<Item = crate::Result<TypedKvPair<K, V>>> + 'static>
+    {
+        let range = (
+            encode_bound::<K>(range.start_bound())?,
+            encode_bound::<K>(range.end_bound())?,
+        );
+
+        Ok(self
+            .inner
+            .inner
+            .range(range)
+            .map(|item| item.and_then(decode_kv::<K, V>)))
+    }
+}
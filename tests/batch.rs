@@ -20,3 +20,38 @@ fn batch_simple() -> fjall::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn batch_savepoints() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    let mut batch = keyspace.batch();
+
+    assert!(!batch.rollback_to_savepoint());
+
+    batch.insert(&partition, "1", "abc");
+    batch.set_savepoint();
+    batch.insert(&partition, "2", "abc");
+    batch.set_savepoint();
+    batch.insert(&partition, "3", "abc");
+    assert_eq!(3, batch.len());
+
+    assert!(batch.rollback_to_savepoint());
+    assert_eq!(2, batch.len());
+
+    assert!(batch.pop_savepoint());
+    assert!(!batch.rollback_to_savepoint());
+    assert_eq!(2, batch.len());
+
+    batch.set_savepoint();
+    batch.commit()?;
+    assert!(!batch.rollback_to_savepoint());
+
+    assert!(partition.contains_key("1")?);
+    assert!(partition.contains_key("2")?);
+    assert!(!partition.contains_key("3")?);
+
+    Ok(())
+}
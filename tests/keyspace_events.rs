@@ -0,0 +1,90 @@
+use fjall::{CompactionReason, Config, Event, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_events_compaction() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).background_threads(false).open()?;
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let events = keyspace.events();
+
+    tree.insert("a", "abc")?;
+    tree.rotate_memtable()?;
+    keyspace.maintenance();
+
+    let events = events.try_iter().collect::<Vec<_>>();
+
+    let started = events
+        .iter()
+        .position(|event| {
+            matches!(
+                event,
+                Event::CompactionStarted {
+                    partition,
+                    reason: CompactionReason::Flush,
+                } if &**partition == "default"
+            )
+        })
+        .expect("compaction should have started");
+
+    assert!(events[started..]
+        .iter()
+        .any(|event| matches!(event, Event::CompactionFinished { error: None, .. })));
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_events_write_stall_pairing() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder)
+        .max_write_buffer_size(1_024 * 1_024)
+        .open()?;
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let events = keyspace.events();
+    let mut collected = vec![];
+
+    let value = vec![0_u8; 32_000];
+
+    // NOTE: Writing faster than the write buffer can be flushed eventually stalls,
+    // and a stall is always resolved before the write returns
+    for x in 0..1_000_u64 {
+        tree.insert(x.to_be_bytes(), &value)?;
+        collected.extend(events.try_iter());
+
+        if collected
+            .iter()
+            .any(|event| matches!(event, Event::WriteStallFinished { .. }))
+        {
+            break;
+        }
+    }
+
+    let mut open_stall = None;
+    let mut pairs = 0;
+
+    for event in collected {
+        match event {
+            Event::WriteStallStarted { partition, reason } => {
+                assert!(open_stall.is_none(), "stall started twice");
+                open_stall = Some((partition, reason));
+            }
+            Event::WriteStallFinished {
+                partition, reason, ..
+            } => {
+                assert_eq!(Some((partition, reason)), open_stall.take());
+                pairs += 1;
+            }
+            _ => {}
+        }
+    }
+
+    assert!(open_stall.is_none());
+    assert!(pairs > 0);
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "failpoints")]
+
+use fjall::{
+    failpoints::{Action, Failpoints},
+    Config, PartitionCreateOptions,
+};
+use test_log::test;
+
+#[test]
+fn keyspace_failpoint_before_rotate() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let failpoints = Failpoints::default();
+        let keyspace = Config::new(&folder)
+            .background_threads(false)
+            .failpoints(failpoints.clone())
+            .open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        partition.insert("a", "abc")?;
+        partition.insert("b", "abc")?;
+
+        failpoints.set("journal::before_rotate", Action::Error);
+        assert!(partition.rotate_memtable().is_err());
+
+        // NOTE: Other keyspaces are not affected
+        let other_folder = tempfile::tempdir()?;
+        let other_keyspace = Config::new(&other_folder)
+            .background_threads(false)
+            .open()?;
+        let other = other_keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        other.insert("a", "abc")?;
+        assert!(other.rotate_memtable()?);
+    }
+
+    let keyspace = Config::new(&folder).background_threads(false).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    assert_eq!(2, partition.len()?);
+    assert!(partition.contains_key("a")?);
+    assert!(partition.contains_key("b")?);
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_failpoint_before_register() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let failpoints = Failpoints::default();
+        let keyspace = Config::new(&folder)
+            .background_threads(false)
+            .failpoints(failpoints.clone())
+            .open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        partition.insert("a", "abc")?;
+        partition.insert("b", "abc")?;
+
+        failpoints.set("flush::before_register", Action::Error);
+        assert!(partition.rotate_memtable()?);
+        keyspace.maintenance();
+
+        // NOTE: The flushed segment was not registered, so the data is still in the journal
+        assert_eq!(0, partition.segment_count());
+        assert_eq!(2, partition.len()?);
+    }
+
+    let keyspace = Config::new(&folder).background_threads(false).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    assert_eq!(2, partition.len()?);
+    assert!(partition.contains_key("a")?);
+    assert!(partition.contains_key("b")?);
+
+    // NOTE: Recovered data can be flushed once the failpoint is gone
+    keyspace.maintenance();
+    assert_eq!(1, partition.segment_count());
+    assert_eq!(2, partition.len()?);
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_key_validator() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder)
+        .key_validator(|partition, key| {
+            if partition == "fixed" && key.len() != 8 {
+                return Err(format!("expected 8 byte key, got {}", key.len()).into());
+            }
+            Ok(())
+        })
+        .open()?;
+    let fixed = keyspace.open_partition("fixed", PartitionCreateOptions::default())?;
+    let other = keyspace.open_partition("other", PartitionCreateOptions::default())?;
+
+    fixed.insert(0_u64.to_be_bytes(), "abc")?;
+    other.insert("a", "abc")?;
+
+    assert!(matches!(
+        fixed.insert("a", "abc"),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+    assert!(matches!(
+        fixed.remove("a"),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+    assert!(matches!(
+        fixed.insert_many([("a", "abc")]),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+
+    let mut batch = keyspace.batch();
+    batch.insert(&other, "b", "abc");
+    batch.insert(&fixed, "b", "abc");
+    assert!(
+        matches!(batch.commit(), Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput)
+    );
+
+    // NOTE: The batch is atomic, so the valid item is not written either
+    assert_eq!(1, other.len()?);
+    assert_eq!(1, fixed.len()?);
+
+    Ok(())
+}
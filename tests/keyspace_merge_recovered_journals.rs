@@ -0,0 +1,57 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: u64 = 10;
+
+fn write_sealed_journals(config: Config, journal_count: u64) -> fjall::Result<()> {
+    let keyspace = config.open()?;
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for journal in 0..journal_count {
+        for x in 0..ITEM_COUNT {
+            tree.insert((journal * ITEM_COUNT + x).to_be_bytes(), "abc")?;
+        }
+        tree.rotate_memtable()?;
+    }
+
+    assert_eq!(0, tree.segment_count());
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_merge_recovered_journals() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    write_sealed_journals(Config::new(&folder).background_threads(false), 3)?;
+
+    let keyspace = Config::new(&folder).background_threads(false).open()?;
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    assert_eq!(3 * ITEM_COUNT as usize, tree.len()?);
+
+    keyspace.maintenance();
+    assert_eq!(1, tree.segment_count());
+    assert_eq!(3 * ITEM_COUNT as usize, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_merge_recovered_journals_disabled() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    write_sealed_journals(Config::new(&folder).background_threads(false), 3)?;
+
+    let keyspace = Config::new(&folder)
+        .background_threads(false)
+        .merge_recovered_journals(false)
+        .open()?;
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    assert_eq!(3 * ITEM_COUNT as usize, tree.len()?);
+
+    keyspace.maintenance();
+    assert_eq!(3, tree.segment_count());
+    assert_eq!(3 * ITEM_COUNT as usize, tree.len()?);
+
+    Ok(())
+}
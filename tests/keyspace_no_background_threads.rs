@@ -0,0 +1,32 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_no_background_threads() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).background_threads(false).open()?;
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    tree.set_max_memtable_size(1_024);
+
+    assert_eq!(0, tree.segment_count());
+
+    // NOTE: Writes flush inline once the memtable is full
+    for x in 0..1_000_u64 {
+        tree.insert(x.to_be_bytes(), "abc")?;
+    }
+
+    assert!(tree.segment_count() > 0);
+    assert_eq!(1_000, tree.len()?);
+
+    tree.insert("a", "abc")?;
+    tree.rotate_memtable()?;
+
+    let segment_count = tree.segment_count();
+    keyspace.maintenance();
+    assert!(tree.segment_count() > 0);
+    assert!(tree.segment_count() <= segment_count + 1);
+    assert_eq!(1_001, tree.len()?);
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn recover_two_unsealed_journals() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let journals_folder = folder.path().join("journals");
+    let stash = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        tree.insert("a", "a")?;
+    }
+
+    // NOTE: Move the first journal out of the way, so the next session
+    // starts a new journal, simulating a crash during journal rotation
+    let first_journal_name = std::fs::read_dir(&journals_folder)?
+        .next()
+        .expect("should have journal")?
+        .file_name();
+    std::fs::rename(
+        journals_folder.join(&first_journal_name),
+        stash.path().join(&first_journal_name),
+    )?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        tree.insert("b", "b")?;
+    }
+
+    std::fs::rename(
+        stash.path().join(&first_journal_name),
+        journals_folder.join(&first_journal_name),
+    )?;
+    assert_eq!(2, std::fs::read_dir(&journals_folder)?.count());
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).open()?;
+        let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        assert_eq!(tree.len()?, 2);
+        assert!(tree.contains_key("a")?);
+        assert!(tree.contains_key("b")?);
+    }
+
+    Ok(())
+}
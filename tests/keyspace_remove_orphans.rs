@@ -0,0 +1,31 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_remove_orphans() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let orphan_path = folder.path().join("replicated.tmp");
+    let unrelated_path = folder.path().join("unrelated.tmp");
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        tree.insert("a", "a")?;
+    }
+
+    std::fs::write(&orphan_path, [0; 4])?;
+    std::fs::write(&unrelated_path, [0; 4])?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        assert!(tree.contains_key("a")?);
+    }
+
+    assert!(!orphan_path.try_exists()?);
+
+    // NOTE: Files that were not written by fjall are never deleted
+    assert!(unrelated_path.try_exists()?);
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_replication() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let primary = Config::new(&folder).open()?;
+    let items = primary.open_partition("items", PartitionCreateOptions::default())?;
+
+    let replica_folder = tempfile::tempdir()?;
+    let replica = Config::new(&replica_folder).open()?;
+    let replicated_items = replica.open_partition("items", PartitionCreateOptions::default())?;
+
+    items.insert("a", "abc")?;
+    items.insert("b", "def")?;
+
+    let mut batch = primary.batch();
+    batch.insert(&items, "c", "ghi");
+    batch.remove(&items, "a");
+    batch.commit()?;
+
+    let batches = primary.journal_tail(0)?;
+    assert_eq!(3, batches.len());
+
+    for batch in &batches {
+        replica.apply_replicated_batch(&batch.bytes)?;
+    }
+
+    assert_eq!(2, replicated_items.len()?);
+    assert!(!replicated_items.contains_key("a")?);
+    assert_eq!(Some("def".as_bytes().into()), replicated_items.get("b")?);
+    assert_eq!(Some("ghi".as_bytes().into()), replicated_items.get("c")?);
+
+    // Replica seqno should be past the applied batches
+    assert_eq!(primary.instant(), replica.instant());
+
+    // Continue from last applied seqno
+    items.insert("d", "jkl")?;
+
+    let last_seqno = batches.last().expect("should exist").seqno;
+    let batches = primary.journal_tail(last_seqno + 1)?;
+    assert_eq!(1, batches.len());
+
+    for batch in &batches {
+        replica.apply_replicated_batch(&batch.bytes)?;
+    }
+
+    assert_eq!(3, replicated_items.len()?);
+
+    // Corrupt batches are rejected
+    let mut bytes = batches.first().expect("should exist").bytes.clone();
+    let len = bytes.len();
+    bytes[len - 10] ^= 0xFF;
+    assert!(replica.apply_replicated_batch(&bytes).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_replication_idempotent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let primary = Config::new(&folder).open()?;
+    let items = primary.open_partition("items", PartitionCreateOptions::default())?;
+
+    items.insert("a", "abc")?;
+    items.insert("b", "def")?;
+
+    let replica_folder = tempfile::tempdir()?;
+
+    {
+        let replica = Config::new(&replica_folder).open()?;
+        let replicated_items =
+            replica.open_partition("items", PartitionCreateOptions::default())?;
+        assert_eq!(None, replica.replicated_seqno());
+
+        for batch in primary.journal_tail(0)? {
+            replica.apply_replicated_batch(&batch.bytes)?;
+        }
+
+        assert_eq!(Some(1), replica.replicated_seqno());
+        assert_eq!(2, replicated_items.len()?);
+    }
+
+    items.remove("a")?;
+
+    {
+        let replica = Config::new(&replica_folder).open()?;
+        let replicated_items =
+            replica.open_partition("items", PartitionCreateOptions::default())?;
+        assert_eq!(Some(1), replica.replicated_seqno());
+
+        // Retrying from the start does not apply old batches again
+        for batch in primary.journal_tail(0)? {
+            replica.apply_replicated_batch(&batch.bytes)?;
+        }
+
+        assert_eq!(Some(2), replica.replicated_seqno());
+        assert_eq!(1, replicated_items.len()?);
+        assert!(!replicated_items.contains_key("a")?);
+    }
+
+    Ok(())
+}
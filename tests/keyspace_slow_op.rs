@@ -0,0 +1,51 @@
+use fjall::{Config, PartitionCreateOptions, SlowOp};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use test_log::test;
+
+#[test]
+fn keyspace_slow_op_observer() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let ops = Arc::new(Mutex::new(vec![]));
+
+    let keyspace = {
+        let ops = ops.clone();
+
+        Config::new(&folder)
+            .background_threads(false)
+            .slow_op_threshold(Duration::ZERO, move |op| {
+                ops.lock().expect("lock is poisoned").push(op.clone());
+            })
+            .open()?
+    };
+    let tree = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    tree.insert("a", "abc")?;
+    tree.insert("b", "abc")?;
+    assert!(tree.get("a")?.is_some());
+    assert_eq!(2, tree.range("a"..).count());
+
+    tree.insert("c", "abc")?;
+    assert_eq!(3, tree.iter().count());
+
+    tree.rotate_memtable()?;
+    keyspace.maintenance();
+
+    let ops = ops.lock().expect("lock is poisoned");
+
+    assert!(ops
+        .iter()
+        .any(|op| matches!(op, SlowOp::Get { key, .. } if &**key == b"a")));
+    assert!(ops
+        .iter()
+        .any(|op| matches!(op, SlowOp::Range { item_count: 2, .. })));
+    assert!(ops
+        .iter()
+        .any(|op| matches!(op, SlowOp::Range { item_count: 3, .. })));
+    assert!(ops.iter().any(|op| matches!(op, SlowOp::Flush { .. })));
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn partition_hash_range() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open()?;
+
+    let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+    assert_eq!(
+        a.hash_range::<&[u8], _>(..)?.0,
+        b.hash_range::<&[u8], _>(..)?.0
+    );
+
+    for x in 0..100_u64 {
+        a.insert(x.to_be_bytes(), x.to_string())?;
+        b.insert(x.to_be_bytes(), x.to_string())?;
+    }
+
+    assert_eq!(
+        a.hash_range::<&[u8], _>(..)?.0,
+        b.hash_range::<&[u8], _>(..)?.0
+    );
+
+    // Sealed data hashes the same as active data
+    a.rotate_memtable()?;
+    assert_eq!(
+        a.hash_range::<&[u8], _>(..)?.0,
+        b.hash_range::<&[u8], _>(..)?.0
+    );
+
+    b.insert(50_u64.to_be_bytes(), "x")?;
+    assert_ne!(
+        a.hash_range::<&[u8], _>(..)?.0,
+        b.hash_range::<&[u8], _>(..)?.0
+    );
+
+    let lo = 0_u64.to_be_bytes();
+    let mid = 50_u64.to_be_bytes();
+    assert_eq!(a.hash_range(lo..mid)?.0, b.hash_range(lo..mid)?.0);
+    assert_ne!(a.hash_range(mid..)?.0, b.hash_range(mid..)?.0);
+
+    // Moving a byte from the key into the value changes the hash
+    let c = keyspace.open_partition("c", PartitionCreateOptions::default())?;
+    let d = keyspace.open_partition("d", PartitionCreateOptions::default())?;
+    c.insert("ab", "c")?;
+    d.insert("a", "bc")?;
+    assert_ne!(
+        c.hash_range::<&[u8], _>(..)?.0,
+        d.hash_range::<&[u8], _>(..)?.0
+    );
+
+    // NOTE: The hash is computed at the returned seqno, so it can be reproduced from a snapshot
+    let (hash, seqno) = a.hash_range::<&[u8], _>(..)?;
+    a.insert(100_u64.to_be_bytes(), "x")?;
+    assert_ne!(hash, a.hash_range::<&[u8], _>(..)?.0);
+    assert_eq!(100, a.snapshot_at(seqno).len()?);
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "sled")]
+
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn partition_import_from_sled() -> fjall::Result<()> {
+    let sled_folder = tempfile::tempdir()?;
+
+    {
+        let db = sled::open(&sled_folder).expect("should open");
+
+        for x in 0u32..100 {
+            db.insert(x.to_be_bytes(), b"abc").expect("should insert");
+        }
+
+        db.flush().expect("should flush");
+    }
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    assert_eq!(100, partition.import_from_sled(&sled_folder)?);
+    assert_eq!(100, partition.len()?);
+    assert!(partition.contains_key(99u32.to_be_bytes())?);
+
+    Ok(())
+}
+
+#[test]
+fn partition_import_from_sled_not_found() -> fjall::Result<()> {
+    let sled_folder = tempfile::tempdir()?;
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let result = partition.import_from_sled(&sled_folder);
+    assert!(matches!(
+        result,
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+
+    // NOTE: No sled database should have been created
+    assert_eq!(0, std::fs::read_dir(&sled_folder)?.count());
+
+    let result = partition.import_from_sled(sled_folder.path().join("missing"));
+    assert!(matches!(
+        result,
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+    assert!(!sled_folder.path().join("missing").try_exists()?);
+
+    Ok(())
+}
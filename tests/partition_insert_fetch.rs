@@ -0,0 +1,50 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const THREADS: u32 = 4;
+const ITEM_COUNT: u32 = 250;
+
+#[test]
+fn partition_insert_fetch_concurrent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let handles = (0..THREADS)
+        .map(|t| {
+            let partition = partition.clone();
+
+            std::thread::spawn(move || -> fjall::Result<Vec<Option<u32>>> {
+                (0..ITEM_COUNT)
+                    .map(|i| {
+                        let value = t * ITEM_COUNT + i;
+                        let prev = partition.insert_fetch("counter", value.to_be_bytes())?;
+
+                        Ok(prev.map(|prev| {
+                            u32::from_be_bytes((*prev).try_into().expect("should be 4 bytes"))
+                        }))
+                    })
+                    .collect()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut prevs = vec![];
+
+    for handle in handles {
+        prevs.extend(handle.join().expect("thread should not panic")?);
+    }
+
+    // NOTE: Every write observes exactly one predecessor,
+    // so each value (except the last one) is returned exactly once
+    let mut seen = prevs.into_iter().flatten().collect::<Vec<_>>();
+    seen.sort_unstable();
+    let len_before = seen.len();
+    seen.dedup();
+
+    assert_eq!(len_before, seen.len());
+    assert_eq!((THREADS * ITEM_COUNT - 1) as usize, seen.len());
+
+    Ok(())
+}
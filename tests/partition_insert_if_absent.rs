@@ -0,0 +1,32 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const THREADS: usize = 8;
+
+#[test]
+fn partition_insert_if_absent_concurrent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let handles = (0..THREADS)
+        .map(|t| {
+            let partition = partition.clone();
+            std::thread::spawn(move || partition.insert_if_absent("lock", t.to_string()))
+        })
+        .collect::<Vec<_>>();
+
+    let mut winners = 0;
+
+    for handle in handles {
+        if handle.join().expect("thread should not panic")? {
+            winners += 1;
+        }
+    }
+
+    assert_eq!(1, winners);
+    assert_eq!(1, partition.len()?);
+
+    Ok(())
+}
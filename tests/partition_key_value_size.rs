@@ -0,0 +1,92 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn partition_key_too_large() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let key = vec![0; u16::MAX as usize + 1];
+
+    assert!(matches!(
+        partition.insert(&key, "abc"),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+    assert!(matches!(
+        partition.remove(&key),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+    assert!(partition.is_empty()?);
+
+    partition.insert(vec![0; u16::MAX as usize], "abc")?;
+    assert_eq!(partition.len()?, 1);
+
+    Ok(())
+}
+
+#[test]
+fn partition_value_too_large() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let value = vec![0; u16::MAX as usize + 1];
+
+    assert!(matches!(
+        partition.insert("a", &value),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+    assert!(partition.is_empty()?);
+
+    partition.insert("a", vec![0; u16::MAX as usize])?;
+    assert_eq!(partition.len()?, 1);
+
+    Ok(())
+}
+
+#[test]
+fn batch_value_too_large() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "abc");
+    batch.insert(&partition, "b", vec![0; u16::MAX as usize + 1]);
+
+    assert!(
+        matches!(batch.commit(), Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput)
+    );
+
+    // NOTE: The batch is atomic, so the valid item is not written either
+    assert!(partition.is_empty()?);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "single_writer_tx")]
+fn write_tx_value_too_large() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open_transactional()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "a", "abc");
+    tx.insert(&partition, "b", vec![0; u16::MAX as usize + 1]);
+
+    assert!(matches!(
+        tx.commit(),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+
+    // NOTE: The transaction is atomic, so the valid item is not written either
+    assert!(keyspace.read_tx().is_empty(&partition)?);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use fjall::{Config, PartitionCreateOptions};
+use std::time::Duration;
+use test_log::test;
+
+#[test]
+fn partition_lock_range_wakeup() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let lock = partition.lock_range(.."m", Duration::from_secs(1))?;
+
+    let handle = {
+        let partition = partition.clone();
+
+        std::thread::spawn(move || -> fjall::Result<()> {
+            let _lock = partition.lock_key("abc", Duration::MAX)?;
+            partition.insert("abc", "def")?;
+            Ok(())
+        })
+    };
+
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!partition.contains_key("abc")?);
+
+    drop(lock);
+    handle.join().expect("should join")?;
+
+    assert!(partition.contains_key("abc")?);
+
+    // NOTE: Disjoint ranges never block each other
+    let _a = partition.lock_range("a".."m", Duration::ZERO)?;
+    let _b = partition.lock_range("m".., Duration::ZERO)?;
+
+    Ok(())
+}
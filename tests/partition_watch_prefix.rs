@@ -0,0 +1,114 @@
+use fjall::{Config, PartitionCreateOptions, WatchEvent};
+use test_log::test;
+
+#[test]
+fn partition_watch_prefix() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    let other = keyspace.open_partition("other", PartitionCreateOptions::default())?;
+
+    let events = partition.watch_prefix("a");
+    let all_events = partition.watch_prefix("");
+
+    let seqno = partition.insert_with_seqno("a1", "abc")?;
+    partition.insert("b1", "abc")?;
+    other.insert("a1", "abc")?;
+
+    assert_eq!(
+        WatchEvent::Insert {
+            key: "a1".as_bytes().into(),
+            value: "abc".as_bytes().into(),
+            seqno,
+        },
+        events.try_recv().expect("should have event"),
+    );
+    assert!(events.try_recv().is_err());
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a2", "def");
+    batch.remove(&partition, "a1");
+    batch.insert(&other, "a3", "def");
+    let seqno = batch.commit_with_seqno()?;
+
+    let received = events.try_iter().collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            WatchEvent::Insert {
+                key: "a2".as_bytes().into(),
+                value: "def".as_bytes().into(),
+                seqno,
+            },
+            WatchEvent::Remove {
+                key: "a1".as_bytes().into(),
+                seqno,
+            },
+        ],
+        received,
+    );
+
+    partition.insert_many([("a3", "abc"), ("b3", "abc")])?;
+    assert_eq!(1, events.try_iter().count());
+
+    assert_eq!(6, all_events.try_iter().count());
+
+    Ok(())
+}
+
+#[test]
+fn partition_watch_prefix_concurrent() -> fjall::Result<()> {
+    use std::collections::HashMap;
+
+    const THREADS: usize = 4;
+    const WRITES: usize = 500;
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let events = partition.watch_prefix("");
+
+    std::thread::scope(|s| {
+        let handles = (0..THREADS)
+            .map(|t| {
+                let partition = partition.clone();
+
+                s.spawn(move || -> fjall::Result<()> {
+                    for i in 0..WRITES {
+                        let key = format!("key{}", i % 10);
+                        partition.insert(key, format!("{t}-{i}"))?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().expect("thread should not panic"))
+    })?;
+
+    let mut received = events.try_iter().collect::<Vec<_>>();
+    assert_eq!(THREADS * WRITES, received.len());
+
+    // NOTE: Delivery order is unspecified, so events need to be ordered by seqno
+    received.sort_by_key(|event| match event {
+        WatchEvent::Insert { seqno, .. } | WatchEvent::Remove { seqno, .. } => *seqno,
+    });
+
+    let mut cache = HashMap::new();
+
+    for event in received {
+        if let WatchEvent::Insert { key, value, .. } = event {
+            cache.insert(key, value);
+        }
+    }
+
+    for (key, value) in cache {
+        assert_eq!(Some(value), partition.get(key)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use fjall::{Config, PartitionCreateOptions, WriteInterceptor};
+use std::sync::Arc;
+use test_log::test;
+
+struct ReadOnlyPrefix(&'static [u8]);
+
+impl WriteInterceptor for ReadOnlyPrefix {
+    fn intercept(
+        &self,
+        key: &[u8],
+        _value_size: usize,
+        _is_tombstone: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if key.starts_with(self.0) {
+            return Err("prefix is read-only".into());
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn partition_write_interceptor() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    partition.insert("ro#a", "abc")?;
+
+    partition.set_write_interceptor(Some(Arc::new(ReadOnlyPrefix(b"ro#"))));
+
+    assert!(matches!(
+        partition.insert("ro#b", "abc"),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::PermissionDenied
+    ));
+    assert!(matches!(
+        partition.remove("ro#a"),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::PermissionDenied
+    ));
+    partition.insert("rw#a", "abc")?;
+    assert_eq!(2, partition.len()?);
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "rw#b", "abc");
+    batch.remove(&partition, "ro#a");
+    assert!(matches!(
+        batch.commit(),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::PermissionDenied
+    ));
+
+    // NOTE: The batch is atomic, so the valid item is not written either
+    assert_eq!(2, partition.len()?);
+
+    partition.set_write_interceptor(None);
+    partition.remove("ro#a")?;
+    assert_eq!(1, partition.len()?);
+
+    Ok(())
+}
+
+struct AuditLog(fjall::PartitionHandle);
+
+impl WriteInterceptor for AuditLog {
+    fn intercept(
+        &self,
+        key: &[u8],
+        _value_size: usize,
+        _is_tombstone: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.0.insert(key, "")?;
+        Ok(())
+    }
+}
+
+#[test]
+fn partition_write_interceptor_writes_to_keyspace() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    let audit = keyspace.open_partition("audit", PartitionCreateOptions::default())?;
+
+    partition.set_write_interceptor(Some(Arc::new(AuditLog(audit.clone()))));
+
+    partition.insert("a", "abc")?;
+    partition.insert_fetch("b", "abc")?;
+    partition.insert_if_absent("c", "abc")?;
+    partition.remove("a")?;
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "d", "abc");
+    batch.commit()?;
+
+    assert_eq!(3, partition.len()?);
+    assert_eq!(4, audit.len()?);
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct CountWrites(std::sync::atomic::AtomicUsize);
+
+impl WriteInterceptor for CountWrites {
+    fn intercept(
+        &self,
+        _key: &[u8],
+        _value_size: usize,
+        _is_tombstone: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[test]
+fn partition_write_interceptor_insert_if_absent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let counter = Arc::new(CountWrites::default());
+    partition.set_write_interceptor(Some(counter.clone()));
+
+    assert!(partition.insert_if_absent("a", "abc")?);
+    assert_eq!(1, counter.0.load(std::sync::atomic::Ordering::Relaxed));
+
+    // NOTE: The key exists, so nothing is written, and the interceptor is not called
+    assert!(!partition.insert_if_absent("a", "def")?);
+    assert_eq!(1, counter.0.load(std::sync::atomic::Ordering::Relaxed));
+    assert_eq!(b"abc", &*partition.get("a")?.expect("should exist"));
+
+    Ok(())
+}
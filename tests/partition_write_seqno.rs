@@ -0,0 +1,38 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn partition_write_seqno() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let first = partition.insert_with_seqno("a", "abc")?;
+    let second = partition.insert_with_seqno("b", "abc")?;
+    let third = partition.remove_with_seqno("a")?;
+    assert!(first < second);
+    assert!(second < third);
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "c", "abc");
+    batch.insert(&partition, "d", "abc");
+    let batch_seqno = batch.commit_with_seqno()?;
+    assert!(third < batch_seqno);
+
+    // NOTE: All items of the batch share the same seqno
+    let snapshot = partition.snapshot_at(batch_seqno);
+    assert!(!snapshot.contains_key("c")?);
+
+    let snapshot = partition.snapshot_at(batch_seqno + 1);
+    assert!(snapshot.contains_key("c")?);
+    assert!(snapshot.contains_key("d")?);
+
+    assert_eq!(batch_seqno + 1, partition.insert_with_seqno("e", "abc")?);
+
+    // NOTE: The plain variants still assign seqnos, they just don't return them
+    partition.insert("f", "abc")?;
+    assert_eq!(batch_seqno + 3, partition.remove_with_seqno("f")?);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: u32 = 1_000;
+
+#[test]
+fn sharded_partition_merge_order() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition =
+        keyspace.open_sharded_partition("default", 4, PartitionCreateOptions::default())?;
+
+    for x in (0..ITEM_COUNT).rev() {
+        partition.insert(x.to_be_bytes(), "abc")?;
+    }
+
+    for shard in partition.shards() {
+        assert!(!shard.is_empty()?);
+    }
+
+    let keys = partition
+        .iter()
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(ITEM_COUNT as usize, keys.len());
+    assert!(keys.windows(2).all(|w| w.first() < w.get(1)));
+
+    assert_eq!(
+        10,
+        partition
+            .range(10u32.to_be_bytes()..20u32.to_be_bytes())
+            .count()
+    );
+    assert_eq!(ITEM_COUNT as usize, partition.len()?);
+
+    drop(partition);
+    drop(keyspace);
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition =
+        keyspace.open_sharded_partition("default", 4, PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT {
+        assert!(partition.contains_key(x.to_be_bytes())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sharded_partition_shard_count_mismatch() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let partition =
+            keyspace.open_sharded_partition("default", 4, PartitionCreateOptions::default())?;
+        partition.insert("a", "abc")?;
+    }
+
+    let keyspace = Config::new(&folder).open()?;
+
+    for shard_count in [2, 8] {
+        let result = keyspace.open_sharded_partition(
+            "default",
+            shard_count,
+            PartitionCreateOptions::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+        ));
+    }
+
+    let partition =
+        keyspace.open_sharded_partition("default", 4, PartitionCreateOptions::default())?;
+    assert!(partition.contains_key("a")?);
+
+    Ok(())
+}
+
+#[test]
+fn sharded_partition_name_collision() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let other = keyspace.open_partition("default_1", PartitionCreateOptions::default())?;
+    other.insert("a", "abc")?;
+
+    assert!(matches!(
+        keyspace.open_sharded_partition("default", 4, PartitionCreateOptions::default()),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::AlreadyExists
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use fjall::{
+    typed::{
+        codec::{BigEndianU64, Utf8},
+        TypedPartition,
+    },
+    Config, PartitionCreateOptions,
+};
+use test_log::test;
+
+#[test]
+fn typed_partition_range() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    let tree = TypedPartition::<BigEndianU64, Utf8>::new(partition);
+
+    for n in [2, 10, 256, 1] {
+        tree.insert(&n, &n.to_string())?;
+    }
+
+    let keys = tree
+        .range(2..=256)?
+        .map(|item| item.map(|(k, _)| k))
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(vec![2, 10, 256], keys);
+
+    assert_eq!(Some((1, "1".to_string())), tree.first_key_value()?);
+    assert_eq!(Some((256, "256".to_string())), tree.last_key_value()?);
+
+    tree.remove(&1)?;
+    assert!(!tree.contains_key(&1)?);
+
+    Ok(())
+}
+
+#[test]
+fn typed_partition_decode_error() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    partition.insert(5u64.to_be_bytes(), [0xFF, 0xFE])?;
+
+    let tree = TypedPartition::<BigEndianU64, Utf8>::new(partition);
+    assert!(
+        matches!(tree.get(&5), Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData)
+    );
+
+    Ok(())
+}